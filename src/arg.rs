@@ -16,26 +16,122 @@ pub struct Arg {
     pub kind: ArgType,
     /// Number of parameters argument takes
     pub parameter_count: ParameterCount,
+    /// Check that every parameter of this argument has to pass
+    pub validator: Option<Validator>,
+    /// Character on which parameters are split into multiple values
+    pub delimiter: Option<char>,
+    /// Whether every parameter has to be a ```key=value``` pair
+    pub key_value: bool,
+    /// Values parameters are restricted to
+    pub possible_values: Option<Vec<String>>,
+    /// Environment variable the parameter is read from when the option does not appear
+    pub env: Option<String>,
+    /// Parameters used when the option does not appear and its environment variable is not set
+    pub default: Option<Vec<String>>,
+    /// Source of candidates for parameters when completion happens at runtime
+    pub completer: Option<Completer>,
+    /// Description shown in help
+    pub help: Option<String>,
+    /// Description shown in long help, falls back to ```help```
+    pub long_help: Option<String>,
+    /// Name of a parameter shown in help, defaults to a name for the value hint or the uppercased name
+    pub value_name: Option<String>,
+    /// Kind of value parameters are, which shell completion completes them with
+    pub value_hint: Option<ValueHint>,
+    /// Heading of the help section the argument is listed under
+    pub heading: Option<String>,
+    /// Set for arguments that are registered by the library itself
+    pub builtin: Option<Builtin>,
+}
+
+impl Arg {
+    /// Name of a parameter shown in help and completion
+    pub(crate) fn value_name(&self) -> String {
+        match (&self.value_name, self.value_hint.and_then(|hint| hint.value_name())) {
+            (Some(value_name), _) => value_name.clone(),
+            (None, Some(value_name)) => value_name.to_string(),
+            (None, None) => self.name.to_uppercase(),
+        }
+    }
 }
 
 impl fmt::Display for Arg {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.short.is_some() && self.long.is_some() {
-            write!(f, "(Argument {} [short: -{}, long: --{} ] )", self.name, self.short.unwrap(), self.long.as_ref().unwrap())
-        } else if self.short.is_some() {
-            write!(f, "(Argument {}[short: -{}])", self.name, self.short.unwrap())
-        } else if self.long.is_some() {
-            write!(f, "(Argument {} [long: --{} ] )", self.name, self.long.as_ref().unwrap())
-        } else {
-            write!(f, "(Argument {})", self.name)
+        match (self.short, self.long.as_ref()) {
+            (Some(short), Some(long)) => write!(f, "(Argument {} [short: -{}, long: --{} ] )", self.name, short, long),
+            (Some(short), None) => write!(f, "(Argument {}[short: -{}])", self.name, short),
+            (None, Some(long)) => write!(f, "(Argument {} [long: --{} ] )", self.name, long),
+            (None, None) => write!(f, "(Argument {})", self.name),
         }
     }
 }
 
+/// Represents an argument which is handled by the library instead of the program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Builtin {
+    /// ```-h/--help```, stops parsing and generates help
+    Help,
+    /// ```-V/--version```, stops parsing and reports the version
+    Version,
+}
+
+/// Represents a check that is run on each parameter of an argument as it is consumed
+pub struct Validator(Box<ValidatorFn>);
+
+type ValidatorFn = dyn Fn(&str) -> Result<(), String>;
+
+impl Validator {
+    /// Wraps a closure which returns ```Err(message)``` for rejected parameters
+    pub fn new<F>(validator: F) -> Self
+    where
+        F: Fn(&str) -> Result<(), String> + 'static,
+    {
+        Validator(Box::new(validator))
+    }
+
+    /// Runs the check on a single parameter
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        (self.0)(value)
+    }
+}
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Validator")
+    }
+}
+
+/// Represents a source of candidates for a parameter of an argument, which is asked at runtime
+pub struct Completer(Box<CompleterFn>);
+
+type CompleterFn = dyn Fn(&str) -> Vec<String>;
+
+impl Completer {
+    /// Wraps a closure which returns candidates for the word under the cursor
+    pub fn new<F>(completer: F) -> Self
+    where
+        F: Fn(&str) -> Vec<String> + 'static,
+    {
+        Completer(Box::new(completer))
+    }
+
+    /// Gets candidates for the word under the cursor
+    pub fn complete(&self, current: &str) -> Vec<String> {
+        (self.0)(current)
+    }
+}
+
+impl fmt::Debug for Completer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Completer")
+    }
+}
+
 /// Represents every possible variation for the amount of Parameters
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum ParameterCount {
     /// This option or subcommand has 0 arguments
+    #[default]
     Zero,
     /// This option or subcommand has more than n arguments
     More(usize),
@@ -43,29 +139,91 @@ pub enum ParameterCount {
     Less(usize),
     /// This option or subcommand has exactly n arguments
     Exact(usize),
+    /// This option or subcommand takes every remaining argument as is, even the ones
+    /// that look like options or subcommands. A single leading '--' is skipped
+    Rest,
 }
 
-impl Default for ParameterCount {
-    fn default() -> Self {
-        ParameterCount::Zero
+impl ParameterCount {
+    /// Checks if an argument with this count can take n parameters
+    pub fn is_satisfied_by(&self, n: usize) -> bool {
+        match *self {
+            ParameterCount::Zero => n == 0,
+            ParameterCount::More(count) => n > count,
+            ParameterCount::Less(count) => n < count,
+            ParameterCount::Exact(count) => n == count,
+            ParameterCount::Rest => true,
+        }
+    }
+
+    /// Checks if an argument with this count which already has n parameters takes another one
+    pub(crate) fn takes_more(&self, n: usize) -> bool {
+        match *self {
+            ParameterCount::Zero => false,
+            ParameterCount::More(_) => true,
+            ParameterCount::Less(count) => n + 1 < count,
+            ParameterCount::Exact(count) => n < count,
+            ParameterCount::Rest => true,
+        }
+    }
+}
+
+impl fmt::Display for ParameterCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterCount::Zero => write!(f, "no"),
+            ParameterCount::More(n) => write!(f, "at least {}", n + 1),
+            ParameterCount::Less(n) => write!(f, "less than {}", n),
+            ParameterCount::Exact(n) => write!(f, "exactly {}", n),
+            ParameterCount::Rest => write!(f, "all remaining"),
+        }
+    }
+}
+
+/// Represents the kind of value a parameter is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueHint {
+    /// Path to a file, shown as ```<FILE>```
+    FilePath,
+    /// Path to a directory, shown as ```<DIR>```
+    DirPath,
+    /// Name of a command or path to an executable, shown as ```<CMD>```
+    Executable,
+    /// Name of a host, shown as ```<HOST>```
+    Hostname,
+    /// Name of a user, shown as ```<USER>```
+    Username,
+    /// URL, shown as ```<URL>```
+    Url,
+    /// Any other value, which is not completed
+    Other,
+}
+
+impl ValueHint {
+    /// Name of a parameter with this hint shown in help
+    pub(crate) fn value_name(&self) -> Option<&'static str> {
+        match self {
+            ValueHint::FilePath => Some("FILE"),
+            ValueHint::DirPath => Some("DIR"),
+            ValueHint::Executable => Some("CMD"),
+            ValueHint::Hostname => Some("HOST"),
+            ValueHint::Username => Some("USER"),
+            ValueHint::Url => Some("URL"),
+            ValueHint::Other => None,
+        }
     }
 }
 
 /// Represent a type of an Argument
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Default)]
 pub enum ArgType {
     /// Does not have '-' or '--' in front of an argument
     Subcommand,
     /// Includes '-' or '--' in front of an argument
+    #[default]
     Option,
 }
 
-impl Default for ArgType {
-    fn default() -> Self {
-        ArgType::Option
-    }
-}
-
 /// Represents final representation of CL Arguments
 /// Key to this HashMap is a name originally assigned to ```Arg``` and
 /// and value is an ```Option<Vec<_>>``` which holds parameters to that arguments
@@ -73,5 +231,9 @@ impl Default for ArgType {
 /// If value is ```None``` that means the argument has not appeared
 ///
 /// Also, there is a special value with name '{program}' which holds parameters that were
-/// not attributed to any other flag or subcommand
-pub type Args = HashMap<String, Option<Vec<String>>>;
\ No newline at end of file
+/// not attributed to any other flag or subcommand, and if pass-through is enabled a special
+/// value with name '{external}' which holds unknown options and their parameters
+pub type Args = HashMap<String, Option<Vec<String>>>;
+
+/// Name of the value in ```Args``` which holds unknown options when pass-through is enabled
+pub const EXTERNAL: &str = "{external}";
\ No newline at end of file
@@ -1,4 +1,6 @@
-use super::Args;
+use std::collections::HashMap;
+
+use super::{Args, EXTERNAL};
 
 /// Checks if argument with the name appears in args
 pub fn appeared(name: &str, args: &Args) -> bool {
@@ -10,4 +12,30 @@ pub fn appeared(name: &str, args: &Args) -> bool {
 /// ```Some(params)``` if argument appeared, else ```None```
 pub fn parameters<'a>(name: &str, args: &'a Args) -> Option<&'a Vec<String>> {
     args.get(name)?.as_ref()
+}
+
+/// Gets unknown options and the parameters that followed them, in order
+/// # Returns
+/// ```Some(params)``` if pass-through is enabled, else ```None```
+pub fn external(args: &Args) -> Option<&Vec<String>> {
+    parameters(EXTERNAL, args)
+}
+
+/// Gets ```key=value``` parameters to an argument with the name as a map
+/// # Returns
+/// ```Some(map)``` if argument appeared, else ```None```
+/// # Note
+/// If a key appears more than once the last value is kept
+pub fn key_values<'a>(name: &str, args: &'a Args) -> Option<HashMap<&'a str, &'a str>> {
+    let params = parameters(name, args)?;
+
+    Some(
+        params
+            .iter()
+            .filter_map(|param| {
+                let index = param.find('=')?;
+                Some((&param[..index], &param[index + 1..]))
+            })
+            .collect(),
+    )
 }
\ No newline at end of file
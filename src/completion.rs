@@ -0,0 +1,224 @@
+mod bash;
+mod dynamic;
+mod elvish;
+mod fish;
+mod powershell;
+mod zsh;
+
+use crate::arg::{Arg, ArgType, ParameterCount};
+use crate::config::Config;
+pub(crate) use dynamic::{candidates, COMPLETE_VAR};
+
+/// Represents a shell completion scripts can be generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    /// GNU Bash
+    Bash,
+    /// Z shell
+    Zsh,
+    /// Friendly interactive shell
+    Fish,
+    /// PowerShell
+    PowerShell,
+    /// Elvish
+    Elvish,
+}
+
+/// Generates a completion script for a program from its Config
+/// # Note
+/// Scripts count parameters the same way parsing does, so parameters are never
+/// completed as options or subcommands
+pub fn completion(config: &Config, shell: Shell, program_name: &str) -> String {
+    match shell {
+        Shell::Bash => bash::script(config, program_name),
+        Shell::Zsh => zsh::script(config, program_name),
+        Shell::Fish => fish::script(config, program_name),
+        Shell::PowerShell => powershell::script(config, program_name),
+        Shell::Elvish => elvish::script(config, program_name),
+    }
+}
+
+/// Generates a completion script for a program which asks the program itself for candidates
+///
+/// The script runs the program with the words on the command line and the environment variable
+/// ```DCLP_COMPLETE``` set to the position of the word under the cursor, where parsing returns
+/// ```Outcome::Completions``` instead of parsing. This way parameters can be completed with
+/// candidates which depend on runtime state, see ```ConfigBuilder::set_completer```
+pub fn completion_shim(shell: Shell, program_name: &str) -> String {
+    dynamic::shim(shell, program_name)
+}
+
+/// Represents how many parameters an argument consumes once it is encountered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Arity {
+    /// At most n parameters, fewer if an option or a subcommand comes first
+    Values(usize),
+    /// Parameters until an option or a subcommand
+    Unbounded,
+    /// Every remaining argument
+    Rest,
+}
+
+impl Arity {
+    fn of(arg: &Arg) -> Self {
+        match arg.parameter_count {
+            ParameterCount::Zero => Arity::Values(0),
+            ParameterCount::Exact(n) => Arity::Values(n),
+            ParameterCount::Less(n) => Arity::Values(n.saturating_sub(1)),
+            ParameterCount::More(_) => Arity::Unbounded,
+            ParameterCount::Rest => Arity::Rest,
+        }
+    }
+
+    /// Arity left after a short option with something attached to it, which is a parameter
+    /// only for key=value arguments, as in ```-Dkey=value```
+    fn attached(arg: &Arg) -> Self {
+        match Arity::of(arg) {
+            Arity::Values(n) if arg.key_value => Arity::Values(n.saturating_sub(1)),
+            arity => arity,
+        }
+    }
+
+    /// Parameters left, as the ```remaining``` variable of the scripts holds them
+    fn remaining(self) -> String {
+        match self {
+            Arity::Values(n) => n.to_string(),
+            Arity::Unbounded => "more".to_string(),
+            Arity::Rest => "rest".to_string(),
+        }
+    }
+}
+
+/// Represents how a word before the cursor is recognized when scripts walk the command line
+enum Word<'a> {
+    /// The word is exactly the text, and starts the argument
+    Exact(String, &'a Arg, Arity),
+    /// The word starts with the text, as a short option with something attached does
+    Prefix(String, &'a Arg, Arity),
+    /// The word is a long option that is not known
+    UnknownLong,
+}
+
+/// Every way a word before the cursor is recognized, in the order parsing recognizes them:
+/// long options, short options with anything after the letter, then subcommands
+/// # Note
+/// Scripts match words against these in order and stop at the first match. A word that
+/// matches none of them is a parameter of the argument before it
+fn words(config: &Config) -> Vec<Word<'_>> {
+    let mut words = vec![];
+
+    for arg in options(config).filter(|arg| arg.long.is_some()) {
+        words.push(Word::Exact(format!("--{}", arg.long.as_ref().unwrap()), arg, Arity::of(arg)));
+    }
+    words.push(Word::UnknownLong);
+
+    for arg in options(config) {
+        if let Some(short) = arg.short {
+            let short = format!("-{}", short);
+            let arity = Arity::of(arg);
+            if arity != Arity::Values(0) {
+                words.push(Word::Exact(short.clone(), arg, arity));
+            }
+            words.push(Word::Prefix(short, arg, Arity::attached(arg)));
+        }
+    }
+
+    for sub in subcommands(config) {
+        words.push(Word::Exact(sub.name.clone(), sub, Arity::of(sub)));
+    }
+
+    words
+}
+
+fn options(config: &Config) -> impl Iterator<Item = &Arg> {
+    config.args.iter().filter(|arg| arg.kind == ArgType::Option)
+}
+
+fn subcommands(config: &Config) -> impl Iterator<Item = &Arg> {
+    config.args.iter().filter(|arg| arg.kind == ArgType::Subcommand)
+}
+
+/// Every way an option can be written without parameters, as in ```--out``` and ```-o```
+fn spellings(arg: &Arg) -> Vec<String> {
+    let long = arg.long.as_ref().map(|long| format!("--{}", long));
+    let short = arg.short.map(|short| format!("-{}", short));
+
+    long.into_iter().chain(short).collect()
+}
+
+/// Turns a program name into something that can be used in the name of a shell function
+fn identifier(program_name: &str) -> String {
+    program_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Quotes a string for shells where single quotes can not be escaped inside single quotes
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Quotes a string for shells where single quotes are escaped by doubling them inside single quotes
+fn quote_doubling(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// First line of the description of an argument
+fn summary(arg: &Arg) -> &str {
+    let help = arg.help.as_deref().unwrap_or_default();
+    help.lines().next().unwrap_or_default()
+}
+
+/// Generates POSIX ```case``` arms which set the argument a word is and how many parameters
+/// it takes, shared by bash and zsh
+fn arities(config: &Config) -> String {
+    words(config)
+        .into_iter()
+        .map(|word| match word {
+            Word::Exact(text, arg, arity) => arm(&quote(&text), &arg.name, arity),
+            Word::Prefix(text, arg, arity) => arm(&format!("{}*", quote(&text)), &arg.name, arity),
+            Word::UnknownLong => "        --*) return 1 ;;\n".to_string(),
+        })
+        .collect()
+}
+
+fn arm(pattern: &str, name: &str, arity: Arity) -> String {
+    format!("        {}) arg={}; remaining={} ;;\n", pattern, quote(name), arity.remaining())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ConfigBuilder;
+
+    #[test]
+    fn arities_follow_parameter_counts() {
+        let config = ConfigBuilder::default()
+            .add_option("out".into(), 'o', "out".into(), ParameterCount::Exact(2))
+            .add_short_flag("verbose".into(), 'v')
+            .add_long_option("tags".into(), "tags".into(), ParameterCount::More(0))
+            .add_short_option("define".into(), 'D', ParameterCount::Exact(1))
+            .add_subcommand("run".into(), ParameterCount::Rest)
+            .add_subcommand("sub".into(), ParameterCount::Less(3))
+            .set_key_value("define")
+            .build();
+
+        assert_eq!(
+            "        '--out') arg='out'; remaining=2 ;;\n\
+             \x20       '--tags') arg='tags'; remaining=more ;;\n\
+             \x20       '--help') arg='help'; remaining=0 ;;\n\
+             \x20       --*) return 1 ;;\n\
+             \x20       '-o') arg='out'; remaining=2 ;;\n\
+             \x20       '-o'*) arg='out'; remaining=2 ;;\n\
+             \x20       '-v'*) arg='verbose'; remaining=0 ;;\n\
+             \x20       '-D') arg='define'; remaining=1 ;;\n\
+             \x20       '-D'*) arg='define'; remaining=0 ;;\n\
+             \x20       '-h'*) arg='help'; remaining=0 ;;\n\
+             \x20       'run') arg='run'; remaining=rest ;;\n\
+             \x20       'sub') arg='sub'; remaining=2 ;;\n",
+            arities(&config)
+        );
+    }
+}
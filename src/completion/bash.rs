@@ -0,0 +1,114 @@
+use crate::arg::ValueHint;
+use crate::config::Config;
+
+use super::{arities, identifier, options, quote, spellings, subcommands};
+
+/// Generates a bash completion script
+///
+/// The script walks the words before the cursor like parsing does, to find out if the word
+/// under the cursor is a parameter. Parameters are completed with possible values, according to
+/// their value hint or with paths, other words with options, subcommands and paths
+pub fn script(config: &Config, program_name: &str) -> String {
+    let function = format!("_{}", identifier(program_name));
+    let options: Vec<_> = options(config).flat_map(spellings).collect();
+    let subcommands: Vec<_> = subcommands(config).map(|sub| sub.name.clone()).collect();
+
+    format!(
+        r#"# bash completion for {program}, generated by dclp
+
+{function}_arity() {{
+    case "$1" in
+{arities}        *) return 1 ;;
+    esac
+}}
+
+{function}_values() {{
+    case "$1" in
+{values}        *) COMPREPLY=($(compgen -f -- "$cur")) ;;
+    esac
+}}
+
+{function}() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" arg="" remaining=0 i
+    COMPREPLY=()
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        [[ $remaining == rest ]] && break
+        if ! {function}_arity "${{COMP_WORDS[i]}}"; then
+            if [[ $remaining == more ]]; then
+                :
+            elif [[ $remaining -gt 0 ]]; then
+                remaining=$((remaining - 1))
+            fi
+        fi
+    done
+
+    if [[ $remaining == rest ]]; then
+        COMPREPLY=($(compgen -f -- "$cur"))
+    elif [[ $remaining != 0 && ( $remaining != more || $cur != -* ) ]]; then
+        {function}_values "$arg"
+    elif [[ $cur == -* ]]; then
+        COMPREPLY=($(compgen -W {options} -- "$cur"))
+    else
+        COMPREPLY=($(compgen -W {subcommands} -- "$cur") $(compgen -f -- "$cur"))
+    fi
+}}
+
+complete -o filenames -F {function} {program}
+"#,
+        program = program_name,
+        function = function,
+        arities = arities(config),
+        values = values(config),
+        options = quote(&options.join(" ")),
+        subcommands = quote(&subcommands.join(" ")),
+    )
+}
+
+/// Generates case arms which complete parameters of an argument with its possible values,
+/// or according to its value hint
+fn values(config: &Config) -> String {
+    config
+        .args
+        .iter()
+        .filter_map(|arg| {
+            let reply = match (&arg.possible_values, arg.value_hint) {
+                (Some(values), _) => format!("$(compgen -W {} -- \"$cur\")", quote(&values.join(" "))),
+                (None, Some(ValueHint::FilePath)) => "$(compgen -f -- \"$cur\")".to_string(),
+                (None, Some(ValueHint::DirPath)) => "$(compgen -d -- \"$cur\")".to_string(),
+                (None, Some(ValueHint::Executable)) => "$(compgen -c -- \"$cur\")".to_string(),
+                (None, Some(ValueHint::Hostname)) => "$(compgen -A hostname -- \"$cur\")".to_string(),
+                (None, Some(ValueHint::Username)) => "$(compgen -u -- \"$cur\")".to_string(),
+                (None, Some(ValueHint::Url)) | (None, Some(ValueHint::Other)) => String::new(),
+                (None, None) => return None,
+            };
+            Some(format!("        {}) COMPREPLY=({}) ;;\n", quote(&arg.name), reply))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{ConfigBuilder, ParameterCount};
+
+    #[test]
+    fn script_completes_options_and_subcommands() {
+        let config = ConfigBuilder::default()
+            .add_long_option("color".into(), "color".into(), ParameterCount::Exact(1))
+            .add_subcommand("build".into(), ParameterCount::Exact(1))
+            .set_possible_values("color", vec!["auto".into(), "never".into()])
+            .set_value_hint("build", ValueHint::DirPath)
+            .build();
+
+        let script = script(&config, "my-prog");
+
+        assert!(script.contains("_my_prog() {"));
+        assert!(script.contains("compgen -W '--color --help -h'"));
+        assert!(script.contains("compgen -W 'build'"));
+        assert!(script.contains("'color') COMPREPLY=($(compgen -W 'auto never' -- \"$cur\")) ;;"));
+        assert!(script.contains("'build') COMPREPLY=($(compgen -d -- \"$cur\")) ;;"));
+        assert!(script.ends_with("complete -o filenames -F _my_prog my-prog\n"));
+    }
+}
@@ -0,0 +1,188 @@
+use crate::arg::Arg;
+use crate::config::Config;
+use crate::parser::{expected, Expected};
+
+use super::{identifier, options, quote, quote_doubling, spellings, Shell};
+
+/// Name of the environment variable a shim sets to the position of the word under the cursor
+/// to request completion at runtime
+pub(crate) const COMPLETE_VAR: &str = "DCLP_COMPLETE";
+
+/// Gets candidates for the word at the position, out of every argument after the program name
+/// # Note
+/// Positions start at 0 like positions of Errors, arguments after the position are ignored.
+/// Whatever can come at the position is completed: parameters with candidates from the completer
+/// of their argument or with its possible values, options when the word starts with '-'
+/// and subcommands
+pub(crate) fn candidates(config: &Config, words: &[String], position: usize) -> Vec<String> {
+    let current = words.get(position).map(String::as_str).unwrap_or_default();
+    let mut candidates = vec![];
+
+    for expected in expected(config, words, position) {
+        match expected {
+            Expected::Value { arg, .. } => {
+                let arg = config.args.iter().find(|a| a.name == arg).unwrap();
+                candidates.extend(values(arg, current));
+            }
+            Expected::Option if current.starts_with('-') => {
+                candidates.extend(options(config).flat_map(spellings));
+            }
+            Expected::Subcommand(names) => candidates.extend(names),
+            Expected::Option | Expected::Positional => {}
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(current))
+        .collect()
+}
+
+fn values(arg: &Arg, current: &str) -> Vec<String> {
+    match (&arg.completer, &arg.possible_values) {
+        (Some(completer), _) => completer.complete(current),
+        (None, Some(values)) => values.clone(),
+        (None, None) => vec![],
+    }
+}
+
+/// Generates a script which completes by asking the program for candidates,
+/// falling back to paths when there are none
+pub(crate) fn shim(shell: Shell, program_name: &str) -> String {
+    let function = format!("_{}", identifier(program_name));
+
+    match shell {
+        Shell::Bash => format!(
+            r#"# bash completion for {program}, generated by dclp, which asks {program} for candidates
+
+{function}() {{
+    local IFS=$'\n'
+    COMPREPLY=($({var}=$((COMP_CWORD - 1)) "${{COMP_WORDS[0]}}" "${{COMP_WORDS[@]:1}}" 2>/dev/null))
+    if [[ ${{#COMPREPLY[@]}} -eq 0 ]]; then
+        COMPREPLY=($(compgen -f -- "${{COMP_WORDS[COMP_CWORD]}}"))
+    fi
+}}
+
+complete -o filenames -F {function} {program}
+"#,
+            program = program_name,
+            function = function,
+            var = COMPLETE_VAR,
+        ),
+        Shell::Zsh => format!(
+            r#"#compdef {program}
+
+# zsh completion for {program}, generated by dclp, which asks {program} for candidates
+
+{function}() {{
+    local -a candidates
+    candidates=(${{(f)"$({var}=$((CURRENT - 2)) "${{words[1]}}" "${{(@)words[2,-1]}}" 2>/dev/null)"}})
+    if (( ${{#candidates}} )); then
+        compadd -a candidates
+    else
+        _files
+    fi
+}}
+
+if [[ $zsh_eval_context[-1] == loadautofunc ]]; then
+    {function} "$@"
+else
+    compdef {function} {program}
+fi
+"#,
+            program = program_name,
+            function = function,
+            var = COMPLETE_VAR,
+        ),
+        Shell::Fish => format!(
+            r#"# fish completion for {program_name}, generated by dclp, which asks {program_name} for candidates
+
+function __fish{function}_complete
+    set -l current (commandline -ct)
+    set -l words (commandline -opc) "$current"
+    env {var}=(math (count $words) - 2) $words 2>/dev/null
+end
+
+complete -c {program} -a '(__fish{function}_complete)'
+"#,
+            program_name = program_name,
+            program = quote(program_name),
+            function = function,
+            var = COMPLETE_VAR,
+        ),
+        Shell::PowerShell => format!(
+            r#"# powershell completion for {program_name}, generated by dclp, which asks {program_name} for candidates
+
+Register-ArgumentCompleter -Native -CommandName {program} -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $words = @($commandAst.CommandElements |
+        Where-Object {{ $_.Extent.StartOffset -lt $cursorPosition }} |
+        ForEach-Object {{ $_.Extent.Text }})
+    if ($wordToComplete -eq '') {{
+        $words += ''
+    }}
+    $arguments = @($words | Select-Object -Skip 1)
+    $env:{var} = $words.Count - 2
+    $candidates = & $words[0] @arguments 2>$null
+    Remove-Item Env:{var}
+
+    $candidates | ForEach-Object {{ [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_) }}
+}}
+"#,
+            program_name = program_name,
+            program = quote_doubling(program_name),
+            var = COMPLETE_VAR,
+        ),
+        Shell::Elvish => format!(
+            r#"# elvish completion for {program_name}, generated by dclp, which asks {program_name} for candidates
+
+set edit:completion:arg-completer[{program}] = {{|@words|
+    var candidates = [(env {var}=(to-string (- (count $words) 2)) $@words 2>/dev/null)]
+    if (== (count $candidates) 0) {{
+        edit:complete-filename $words[-1]
+    }} else {{
+        all $candidates
+    }}
+}}
+"#,
+            program_name = program_name,
+            program = quote_doubling(program_name),
+            var = COMPLETE_VAR,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{ConfigBuilder, ParameterCount};
+
+    #[test]
+    fn candidates_follow_parameters() {
+        let config = ConfigBuilder::default()
+            .add_option("branch".into(), 'b', "branch".into(), ParameterCount::Exact(2))
+            .add_long_option("color".into(), "color".into(), ParameterCount::More(0))
+            .add_subcommand("build".into(), ParameterCount::Zero)
+            .add_subcommand("bench".into(), ParameterCount::Rest)
+            .set_completer("branch", |current| {
+                vec!["main".into(), "master".into(), format!("{}-new", current)]
+            })
+            .set_possible_values("color", vec!["auto".into(), "always".into(), "never".into()])
+            .set_key_value("branch")
+            .build();
+        let words = |words: &[&str]| words.iter().map(|word| word.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(vec!["build", "bench"], candidates(&config, &words(&["b"]), 0));
+        assert_eq!(vec!["--branch", "--color", "--help"], candidates(&config, &words(&["--"]), 0));
+        assert_eq!(vec!["main", "master", "ma-new"], candidates(&config, &words(&["-b", "ma"]), 1));
+        assert_eq!(vec!["main", "master", "m-new"], candidates(&config, &words(&["-bx=1", "m"]), 1));
+        assert_eq!(vec!["build", "bench"], candidates(&config, &words(&["-bx=1", "y", "b"]), 2));
+        assert_eq!(vec!["main", "master", "-new"], candidates(&config, &words(&["-b", "x=1", ""]), 2));
+        assert_eq!(vec!["auto", "always"], candidates(&config, &words(&["--color", "never", "a"]), 2));
+        assert_eq!(vec!["--color"], candidates(&config, &words(&["--color", "never", "--c"]), 2));
+        assert_eq!(Vec::<String>::new(), candidates(&config, &words(&["bench", "--", "-"]), 2));
+        assert_eq!(vec!["--help"], candidates(&config, &words(&["--he", "x"]), 0));
+    }
+}
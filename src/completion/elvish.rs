@@ -0,0 +1,196 @@
+use crate::arg::ValueHint;
+use crate::config::Config;
+
+use super::{options, quote_doubling, spellings, subcommands, summary, words, Arity, Word};
+
+/// Generates an Elvish argument completer
+///
+/// The completer walks the words before the cursor like parsing does. Parameters are
+/// completed with possible values, nothing if their value hint is not a path, or paths.
+/// Other words are completed with described options, or with described subcommands and paths
+pub fn script(config: &Config, program_name: &str) -> String {
+    format!(
+        r#"# elvish completion for {program_name}, generated by dclp
+
+use str
+
+set edit:completion:arg-completer[{program}] = {{|@words|
+    var cand = {{|text desc| edit:complex-candidate $text &display=$text' '$desc }}
+    var count = {{|remaining| if (has-value [0 more] $remaining) {{ put $remaining }} else {{ to-string (- $remaining 1) }} }}
+    var arg remaining = '' 0
+    for word $words[1..-1] {{
+        if (==s $remaining rest) {{
+            break
+        }}
+{arms}        }} else {{
+            set remaining = ($count $remaining)
+        }}
+    }}
+
+    var current = $words[-1]
+    if (==s $remaining rest) {{
+        edit:complete-filename $current
+    }} elif (and (!=s $remaining 0) (or (!=s $remaining more) (not (str:has-prefix $current -)))) {{
+{values}    }} elif (str:has-prefix $current -) {{
+{options}    }} else {{
+{subcommands}        edit:complete-filename $current
+    }}
+}}
+"#,
+        program_name = program_name,
+        program = quote_doubling(program_name),
+        arms = arms(config),
+        values = values(config),
+        options = options(config)
+            .flat_map(|arg| spellings(arg).into_iter().map(move |spelling| candidate(&spelling, summary(arg))))
+            .collect::<String>(),
+        subcommands = subcommands(config)
+            .map(|sub| candidate(&sub.name, summary(sub)))
+            .collect::<String>(),
+    )
+}
+
+/// Generates an ```if``` chain which sets the argument a word is and how many parameters it takes
+fn arms(config: &Config) -> String {
+    let arms = words(config).into_iter().map(|word| match word {
+        Word::Exact(text, arg, arity) => arm(&format!("==s $word {}", quote_doubling(&text)), &arg.name, arity),
+        Word::Prefix(text, arg, arity) => {
+            arm(&format!("str:has-prefix $word {}", quote_doubling(&text)), &arg.name, arity)
+        }
+        Word::UnknownLong => (
+            "str:has-prefix $word --".to_string(),
+            "set remaining = ($count $remaining)".to_string(),
+        ),
+    });
+
+    let mut chain = String::new();
+    for (i, (condition, body)) in arms.enumerate() {
+        let keyword = if i == 0 { "        if" } else { "        } elif" };
+        chain.push_str(&format!("{} ({}) {{\n            {}\n", keyword, condition, body));
+    }
+
+    chain
+}
+
+fn arm(condition: &str, name: &str, arity: Arity) -> (String, String) {
+    (
+        condition.to_string(),
+        format!("set arg remaining = {} {}", quote_doubling(name), arity.remaining()),
+    )
+}
+
+/// Generates conditions which complete parameters of an argument with its possible values,
+/// or with nothing for value hints which are not paths
+fn values(config: &Config) -> String {
+    let mut chain = String::new();
+
+    for arg in &config.args {
+        let completion = match (&arg.possible_values, arg.value_hint) {
+            (Some(values), _) => {
+                let values: Vec<_> = values.iter().map(|value| quote_doubling(value)).collect();
+                format!("put {}", values.join(" "))
+            }
+            (None, Some(ValueHint::Hostname))
+            | (None, Some(ValueHint::Username))
+            | (None, Some(ValueHint::Url))
+            | (None, Some(ValueHint::Other)) => "nop".to_string(),
+            (None, _) => continue,
+        };
+        let keyword = if chain.is_empty() { "if" } else { "} elif" };
+        chain.push_str(&format!(
+            "        {} (==s $arg {}) {{\n            {}\n",
+            keyword,
+            quote_doubling(&arg.name),
+            completion
+        ));
+    }
+
+    if chain.is_empty() {
+        "        edit:complete-filename $current\n".to_string()
+    } else {
+        chain + "        } else {\n            edit:complete-filename $current\n        }\n"
+    }
+}
+
+/// Generates a completion with its description, which is the completion itself if it has none
+fn candidate(text: &str, description: &str) -> String {
+    let description = if description.is_empty() { text } else { description };
+
+    format!("        $cand {} {}\n", quote_doubling(text), quote_doubling(description))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{ConfigBuilder, ParameterCount};
+
+    #[test]
+    fn golden_script() {
+        let config = ConfigBuilder::default()
+            .add_option("out".into(), 'o', "out".into(), ParameterCount::Exact(1))
+            .add_short_flag("verbose".into(), 'v')
+            .add_subcommand("run".into(), ParameterCount::Rest)
+            .set_help("out", "Write to the user's file".into())
+            .set_possible_values("out", vec!["a.txt".into(), "b.txt".into()])
+            .build();
+
+        assert_eq!(
+            r#"# elvish completion for prog, generated by dclp
+
+use str
+
+set edit:completion:arg-completer['prog'] = {|@words|
+    var cand = {|text desc| edit:complex-candidate $text &display=$text' '$desc }
+    var count = {|remaining| if (has-value [0 more] $remaining) { put $remaining } else { to-string (- $remaining 1) } }
+    var arg remaining = '' 0
+    for word $words[1..-1] {
+        if (==s $remaining rest) {
+            break
+        }
+        if (==s $word '--out') {
+            set arg remaining = 'out' 1
+        } elif (==s $word '--help') {
+            set arg remaining = 'help' 0
+        } elif (str:has-prefix $word --) {
+            set remaining = ($count $remaining)
+        } elif (==s $word '-o') {
+            set arg remaining = 'out' 1
+        } elif (str:has-prefix $word '-o') {
+            set arg remaining = 'out' 1
+        } elif (str:has-prefix $word '-v') {
+            set arg remaining = 'verbose' 0
+        } elif (str:has-prefix $word '-h') {
+            set arg remaining = 'help' 0
+        } elif (==s $word 'run') {
+            set arg remaining = 'run' rest
+        } else {
+            set remaining = ($count $remaining)
+        }
+    }
+
+    var current = $words[-1]
+    if (==s $remaining rest) {
+        edit:complete-filename $current
+    } elif (and (!=s $remaining 0) (or (!=s $remaining more) (not (str:has-prefix $current -)))) {
+        if (==s $arg 'out') {
+            put 'a.txt' 'b.txt'
+        } else {
+            edit:complete-filename $current
+        }
+    } elif (str:has-prefix $current -) {
+        $cand '--out' 'Write to the user''s file'
+        $cand '-o' 'Write to the user''s file'
+        $cand '-v' '-v'
+        $cand '--help' 'Prints help information'
+        $cand '-h' 'Prints help information'
+    } else {
+        $cand 'run' 'run'
+        edit:complete-filename $current
+    }
+}
+"#,
+            script(&config, "prog")
+        );
+    }
+}
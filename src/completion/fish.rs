@@ -0,0 +1,199 @@
+use crate::arg::{Arg, ValueHint};
+use crate::config::Config;
+
+use super::{identifier, options, subcommands, words, Arity, Word};
+
+/// Takes one of the remaining parameters in fish
+const COUNT_PARAMETER: &str = "if not contains -- $remaining 0 more; set remaining (math $remaining - 1); end";
+
+/// Generates a fish completion script
+///
+/// Every completion is conditioned on a function which walks the words before the cursor
+/// like parsing does. Parameters are completed with possible values or according to their
+/// value hint, falling back to paths, other words with options, described subcommands and paths
+pub fn script(config: &Config, program_name: &str) -> String {
+    let program = quote(program_name);
+    let function = format!("__fish_{}_parameter", identifier(program_name));
+    let mut script = format!(
+        r#"# fish completion for {program_name}, generated by dclp
+
+# Checks if the word under the cursor is a parameter, with --required one that has to be given,
+# with the name of an argument one of that argument
+function {function}
+    set -l arg ''
+    set -l remaining 0
+    for word in (commandline -opc)[2..-1]
+        test $remaining = rest; and break
+        switch $word
+{arms}        end
+    end
+
+    switch "$argv[1]"
+        case ''
+            test $remaining != 0
+        case --required
+            test $remaining != 0 -a $remaining != more
+        case '*'
+            test $remaining != 0 -a "$arg" = "$argv[1]"
+    end
+end
+
+"#,
+        program_name = program_name,
+        function = function,
+        arms = arms(config),
+    );
+
+    for arg in options(config) {
+        let mut line = format!("complete -c {} -n 'not {} --required'", program, function);
+        if let Some(short) = arg.short {
+            line.push_str(&format!(" -s {}", quote(&short.to_string())));
+        }
+        if let Some(long) = &arg.long {
+            line.push_str(&format!(" -l {}", quote(long)));
+        }
+        script.push_str(&described(line, arg));
+    }
+
+    for sub in subcommands(config) {
+        let line = format!("complete -c {} -n 'not {}' -a {}", program, function, quote(&quote(&sub.name)));
+        script.push_str(&described(line, sub));
+    }
+
+    for arg in &config.args {
+        let completion = match (&arg.possible_values, arg.value_hint) {
+            (Some(values), _) => {
+                let values: Vec<_> = values.iter().map(|value| quote(value)).collect();
+                format!("-f -a {}", quote(&values.join(" ")))
+            }
+            (None, Some(hint)) => completion(hint).to_string(),
+            (None, None) => continue,
+        };
+        script.push_str(&format!(
+            "complete -c {} -n {} {}\n",
+            program,
+            quote(&format!("{} {}", function, quote(&arg.name))),
+            completion
+        ));
+    }
+
+    script
+}
+
+/// Generates ```switch``` cases which set the argument a word is and how many parameters it takes
+fn arms(config: &Config) -> String {
+    let mut arms: String = words(config)
+        .into_iter()
+        .map(|word| match word {
+            Word::Exact(text, arg, arity) => arm(&quote(&text), &arg.name, arity),
+            Word::Prefix(text, arg, arity) => arm(&quote(&format!("{}*", text)), &arg.name, arity),
+            Word::UnknownLong => format!("            case '--*'\n                {}\n", COUNT_PARAMETER),
+        })
+        .collect();
+    arms.push_str(&format!("            case '*'\n                {}\n", COUNT_PARAMETER));
+
+    arms
+}
+
+fn arm(pattern: &str, name: &str, arity: Arity) -> String {
+    format!(
+        "            case {}\n                set arg {}; set remaining {}\n",
+        pattern,
+        quote(name),
+        arity.remaining()
+    )
+}
+
+/// Options of ```complete``` which complete parameters with the value hint
+fn completion(hint: ValueHint) -> &'static str {
+    match hint {
+        ValueHint::FilePath => "-F",
+        ValueHint::DirPath => "-f -a '(__fish_complete_directories (commandline -ct))'",
+        ValueHint::Executable => "-f -a '(__fish_complete_command)'",
+        ValueHint::Hostname => "-f -a '(__fish_print_hostnames)'",
+        ValueHint::Username => "-f -a '(__fish_complete_users)'",
+        ValueHint::Url | ValueHint::Other => "-f",
+    }
+}
+
+/// Finishes a completion with the first line of the description of the argument
+fn described(mut line: String, arg: &Arg) -> String {
+    if let Some(help) = arg.help.as_deref().and_then(|help| help.lines().next()) {
+        line.push_str(&format!(" -d {}", quote(help)));
+    }
+    line.push('\n');
+
+    line
+}
+
+/// Quotes a string for fish, where single quotes and backslashes are escaped inside single quotes
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{ConfigBuilder, ParameterCount};
+
+    #[test]
+    fn arms_follow_parameter_counts() {
+        let config = ConfigBuilder::default()
+            .add_option("out".into(), 'o', "out".into(), ParameterCount::Exact(2))
+            .add_short_flag("verbose".into(), 'v')
+            .add_subcommand("run".into(), ParameterCount::Rest)
+            .build();
+
+        assert_eq!(
+            "            case '--out'\n\
+             \x20               set arg 'out'; set remaining 2\n\
+             \x20           case '--help'\n\
+             \x20               set arg 'help'; set remaining 0\n\
+             \x20           case '--*'\n\
+             \x20               if not contains -- $remaining 0 more; set remaining (math $remaining - 1); end\n\
+             \x20           case '-o'\n\
+             \x20               set arg 'out'; set remaining 2\n\
+             \x20           case '-o*'\n\
+             \x20               set arg 'out'; set remaining 2\n\
+             \x20           case '-v*'\n\
+             \x20               set arg 'verbose'; set remaining 0\n\
+             \x20           case '-h*'\n\
+             \x20               set arg 'help'; set remaining 0\n\
+             \x20           case 'run'\n\
+             \x20               set arg 'run'; set remaining rest\n\
+             \x20           case '*'\n\
+             \x20               if not contains -- $remaining 0 more; set remaining (math $remaining - 1); end\n",
+            arms(&config)
+        );
+    }
+
+    #[test]
+    fn script_completes_options_subcommands_and_values() {
+        let config = ConfigBuilder::default()
+            .add_long_option("color".into(), "color".into(), ParameterCount::Exact(1))
+            .add_subcommand("build".into(), ParameterCount::Zero)
+            .add_short_option("user".into(), 'u', ParameterCount::Exact(1))
+            .set_help("color", "When to use colors".into())
+            .set_help("build", "Builds the project's targets".into())
+            .set_possible_values("color", vec!["auto".into(), "never".into()])
+            .set_value_hint("user", ValueHint::Username)
+            .build();
+
+        let script = script(&config, "my-prog");
+
+        assert!(script.contains("function __fish_my_prog_parameter\n"));
+        assert!(script.contains(
+            "complete -c 'my-prog' -n 'not __fish_my_prog_parameter --required' -l 'color' -d 'When to use colors'\n"
+        ));
+        assert!(script.contains(
+            "complete -c 'my-prog' -n 'not __fish_my_prog_parameter' -a '\\'build\\'' -d 'Builds the project\\'s targets'\n"
+        ));
+        assert!(script.contains(
+            "complete -c 'my-prog' -n '__fish_my_prog_parameter \\'color\\'' -f -a '\\'auto\\' \\'never\\''\n"
+        ));
+        assert!(script.ends_with(
+            "complete -c 'my-prog' -n '__fish_my_prog_parameter \\'user\\'' -f -a '(__fish_complete_users)'\n"
+        ));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::arg::ValueHint;
+use crate::config::Config;
+
+use super::{options, quote_doubling, spellings, subcommands, summary, words, Arity, Word};
+
+/// Takes one of the remaining parameters in PowerShell, where ```$remaining``` is a number or a word
+const COUNT_PARAMETER: &str = "if (\"$remaining\" -notin '0', 'more') { $remaining = $remaining - 1 }";
+
+/// Generates a PowerShell argument completer
+///
+/// The completer walks the words before the cursor like parsing does. Parameters are
+/// completed with possible values, commands for the executable value hint or paths,
+/// other words with described options or subcommands
+pub fn script(config: &Config, program_name: &str) -> String {
+    format!(
+        r#"# powershell completion for {program_name}, generated by dclp
+
+Register-ArgumentCompleter -Native -CommandName {program} -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $arg = ''
+    $remaining = 0
+    $words = $commandAst.CommandElements |
+        Where-Object {{ $_.Extent.EndOffset -lt $cursorPosition }} |
+        Select-Object -Skip 1
+    foreach ($word in $words) {{
+        if ("$remaining" -eq 'rest') {{ break }}
+        switch -CaseSensitive ($word.Extent.Text) {{
+{arms}        }}
+    }}
+
+    if ("$remaining" -eq 'rest') {{
+        return
+    }} elseif ("$remaining" -ne '0' -and ("$remaining" -ne 'more' -or -not $wordToComplete.StartsWith('-'))) {{
+        $kind = 'ParameterValue'
+        $candidates = @(switch -CaseSensitive ($arg) {{
+{values}        }})
+    }} elseif ($wordToComplete.StartsWith('-')) {{
+        $kind = 'ParameterName'
+        $candidates = @(
+{options}        )
+    }} else {{
+        $kind = 'Command'
+        $candidates = @(
+{subcommands}        )
+    }}
+
+    $candidates |
+        Where-Object {{ $_ -and $_[0].StartsWith($wordToComplete, [System.StringComparison]::Ordinal) }} |
+        ForEach-Object {{ [System.Management.Automation.CompletionResult]::new($_[0], $_[0], $kind, $_[1]) }}
+}}
+"#,
+        program_name = program_name,
+        program = quote_doubling(program_name),
+        arms = arms(config),
+        values = values(config),
+        options = options(config)
+            .flat_map(|arg| spellings(arg).into_iter().map(move |spelling| candidate(&spelling, summary(arg))))
+            .collect::<String>(),
+        subcommands = subcommands(config)
+            .map(|sub| candidate(&sub.name, summary(sub)))
+            .collect::<String>(),
+    )
+}
+
+/// Generates ```switch``` clauses which set the argument a word is and how many parameters it takes
+fn arms(config: &Config) -> String {
+    let mut arms: String = words(config)
+        .into_iter()
+        .map(|word| match word {
+            Word::Exact(text, arg, arity) => arm(&quote_doubling(&text), &arg.name, arity),
+            Word::Prefix(text, arg, arity) => {
+                arm(&format!("{{ $_.StartsWith({}) }}", quote_doubling(&text)), &arg.name, arity)
+            }
+            Word::UnknownLong => format!("            {{ $_.StartsWith('--') }} {{ {}; break }}\n", COUNT_PARAMETER),
+        })
+        .collect();
+    arms.push_str(&format!("            default {{ {} }}\n", COUNT_PARAMETER));
+
+    arms
+}
+
+fn arm(condition: &str, name: &str, arity: Arity) -> String {
+    let remaining = match arity {
+        Arity::Values(n) => n.to_string(),
+        arity => quote_doubling(&arity.remaining()),
+    };
+
+    format!(
+        "            {} {{ $arg = {}; $remaining = {}; break }}\n",
+        condition,
+        quote_doubling(name),
+        remaining
+    )
+}
+
+/// Generates switch clauses which complete parameters of an argument with its possible values,
+/// or with commands for the executable value hint
+/// # Note
+/// PowerShell completes paths when there are no candidates, so other value hints fall back to paths
+fn values(config: &Config) -> String {
+    config
+        .args
+        .iter()
+        .filter_map(|arg| {
+            let candidates = match (&arg.possible_values, arg.value_hint) {
+                (Some(values), _) => {
+                    let values: Vec<_> = values
+                        .iter()
+                        .map(|value| format!(",({0}, {0})", quote_doubling(value)))
+                        .collect();
+                    values.join("; ")
+                }
+                (None, Some(ValueHint::Executable)) => {
+                    "Get-Command -CommandType Application -Name \"$wordToComplete*\" | ForEach-Object { ,($_.Name, $_.Name) }".to_string()
+                }
+                (None, _) => return None,
+            };
+            Some(format!("            {} {{ {} }}\n", quote_doubling(&arg.name), candidates))
+        })
+        .collect()
+}
+
+/// Generates a completion with its description, which is the completion itself if it has none
+fn candidate(text: &str, description: &str) -> String {
+    let description = if description.is_empty() { text } else { description };
+
+    format!("            ,({}, {})\n", quote_doubling(text), quote_doubling(description))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{ConfigBuilder, ParameterCount};
+
+    #[test]
+    fn golden_script() {
+        let config = ConfigBuilder::default()
+            .add_option("out".into(), 'o', "out".into(), ParameterCount::Exact(1))
+            .add_short_flag("verbose".into(), 'v')
+            .add_long_option("mode".into(), "mode".into(), ParameterCount::Exact(1))
+            .add_subcommand("run".into(), ParameterCount::Rest)
+            .set_help("out", "Write to the user's file".into())
+            .set_possible_values("out", vec!["a.txt".into(), "b.txt".into()])
+            .set_possible_values("mode", vec!["fast".into()])
+            .build();
+
+        assert_eq!(
+            r#"# powershell completion for prog, generated by dclp
+
+Register-ArgumentCompleter -Native -CommandName 'prog' -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $arg = ''
+    $remaining = 0
+    $words = $commandAst.CommandElements |
+        Where-Object { $_.Extent.EndOffset -lt $cursorPosition } |
+        Select-Object -Skip 1
+    foreach ($word in $words) {
+        if ("$remaining" -eq 'rest') { break }
+        switch -CaseSensitive ($word.Extent.Text) {
+            '--out' { $arg = 'out'; $remaining = 1; break }
+            '--mode' { $arg = 'mode'; $remaining = 1; break }
+            '--help' { $arg = 'help'; $remaining = 0; break }
+            { $_.StartsWith('--') } { if ("$remaining" -notin '0', 'more') { $remaining = $remaining - 1 }; break }
+            '-o' { $arg = 'out'; $remaining = 1; break }
+            { $_.StartsWith('-o') } { $arg = 'out'; $remaining = 1; break }
+            { $_.StartsWith('-v') } { $arg = 'verbose'; $remaining = 0; break }
+            { $_.StartsWith('-h') } { $arg = 'help'; $remaining = 0; break }
+            'run' { $arg = 'run'; $remaining = 'rest'; break }
+            default { if ("$remaining" -notin '0', 'more') { $remaining = $remaining - 1 } }
+        }
+    }
+
+    if ("$remaining" -eq 'rest') {
+        return
+    } elseif ("$remaining" -ne '0' -and ("$remaining" -ne 'more' -or -not $wordToComplete.StartsWith('-'))) {
+        $kind = 'ParameterValue'
+        $candidates = @(switch -CaseSensitive ($arg) {
+            'out' { ,('a.txt', 'a.txt'); ,('b.txt', 'b.txt') }
+            'mode' { ,('fast', 'fast') }
+        })
+    } elseif ($wordToComplete.StartsWith('-')) {
+        $kind = 'ParameterName'
+        $candidates = @(
+            ,('--out', 'Write to the user''s file')
+            ,('-o', 'Write to the user''s file')
+            ,('-v', '-v')
+            ,('--mode', '--mode')
+            ,('--help', 'Prints help information')
+            ,('-h', 'Prints help information')
+        )
+    } else {
+        $kind = 'Command'
+        $candidates = @(
+            ,('run', 'run')
+        )
+    }
+
+    $candidates |
+        Where-Object { $_ -and $_[0].StartsWith($wordToComplete, [System.StringComparison]::Ordinal) } |
+        ForEach-Object { [System.Management.Automation.CompletionResult]::new($_[0], $_[0], $kind, $_[1]) }
+}
+"#,
+            script(&config, "prog")
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use crate::arg::{Arg, ArgType, Builtin, ParameterCount, ValueHint};
+use crate::config::Config;
+
+use super::{arities, identifier, options, quote, spellings, subcommands, summary};
+
+/// Generates a zsh completion function
+///
+/// Options are completed by ```_arguments``` with their descriptions, other words walk the
+/// words before the cursor like bash does, to complete parameters of subcommands with
+/// possible values or according to their value hint, and everything else with described
+/// subcommands and paths
+pub fn script(config: &Config, program_name: &str) -> String {
+    let function = format!("_{}", identifier(program_name));
+    let commands: Vec<_> = subcommands(config)
+        .map(|sub| quote(&format!("{}:{}", escape(&sub.name, ":"), summary(sub))))
+        .collect();
+
+    format!(
+        r#"#compdef {program}
+
+# zsh completion for {program}, generated by dclp
+
+{function}_arity() {{
+    case "$1" in
+{arities}        *) return 1 ;;
+    esac
+}}
+
+{function}_values() {{
+    case "$1" in
+{values}        *) _files ;;
+    esac
+}}
+
+{function}() {{
+    local context state state_descr line arg="" remaining=0 i
+    typeset -A opt_args
+    local -a commands
+    commands=({commands})
+
+    _arguments \
+{specs}        '*: :->positional' && return 0
+    [[ $state == positional ]] || return 1
+
+    for ((i = 2; i < CURRENT; i++)); do
+        [[ $remaining == rest ]] && break
+        if ! {function}_arity "${{words[i]}}"; then
+            if [[ $remaining == more ]]; then
+                :
+            elif [[ $remaining -gt 0 ]]; then
+                remaining=$((remaining - 1))
+            fi
+        fi
+    done
+
+    if [[ $remaining == rest ]]; then
+        _files
+    elif [[ $remaining != 0 ]]; then
+        {function}_values "$arg"
+    else
+        _describe -t commands command commands
+        _files
+    fi
+}}
+
+if [[ $zsh_eval_context[-1] == loadautofunc ]]; then
+    {function} "$@"
+else
+    compdef {function} {program}
+fi
+"#,
+        program = program_name,
+        function = function,
+        arities = arities(config),
+        values = values(config),
+        commands = commands.join(" "),
+        specs = specs(config),
+    )
+}
+
+/// Generates an ```_arguments``` spec for every way an option can be written, one per line
+///
+/// Help and version exclude every other argument, flags exclude their other spelling,
+/// options exclude the other options of their groups and options with parameters can be
+/// repeated, since their parameters are collected
+fn specs(config: &Config) -> String {
+    let mut specs = String::new();
+
+    for arg in options(config) {
+        let takes_parameters = arg.parameter_count != ParameterCount::Zero;
+        let mut excluded = if takes_parameters { vec![] } else { spellings(arg) };
+        excluded.extend(
+            grouped_with(config, arg)
+                .filter(|other| other.kind == ArgType::Option)
+                .flat_map(spellings),
+        );
+        let exclusion = match arg.builtin {
+            Some(Builtin::Help) | Some(Builtin::Version) => "(- *)".to_string(),
+            None if excluded.is_empty() => "*".to_string(),
+            None if takes_parameters => format!("({})*", excluded.join(" ")),
+            None => format!("({})", excluded.join(" ")),
+        };
+        let description = match &arg.help {
+            Some(_) => format!("[{}]", escape(summary(arg), "]")),
+            None => String::new(),
+        };
+
+        for spelling in spellings(arg) {
+            let attachable = if arg.key_value && !spelling.starts_with("--") { "+" } else { "" };
+            let spec = format!("{}{}{}{}{}", exclusion, spelling, attachable, description, parameters(arg));
+            specs.push_str(&format!("        {} \\\n", quote(&spec)));
+        }
+    }
+
+    specs
+}
+
+/// Other arguments which are in a group with the argument
+fn grouped_with<'a>(config: &'a Config, arg: &'a Arg) -> impl Iterator<Item = &'a Arg> {
+    config.args.iter().filter(move |other| {
+        other.name != arg.name
+            && config
+                .groups
+                .iter()
+                .any(|(_, names)| names.contains(&arg.name) && names.contains(&other.name))
+    })
+}
+
+/// Describes the parameters of an option for ```_arguments```, as in ```:OUT:_files```
+fn parameters(arg: &Arg) -> String {
+    let value_name = arg.value_name();
+    let action = match &arg.possible_values {
+        Some(values) => {
+            let values: Vec<_> = values.iter().map(|value| escape(value, " :()")).collect();
+            format!("({})", values.join(" "))
+        }
+        None => action(arg.value_hint.unwrap_or(ValueHint::FilePath)).to_string(),
+    };
+    let parameter = format!("{}:{}", escape(&value_name, ":"), action);
+
+    match arg.parameter_count {
+        ParameterCount::Zero => String::new(),
+        ParameterCount::Exact(n) => format!(":{}", parameter).repeat(n),
+        ParameterCount::More(n) => format!(":{}", parameter).repeat(n + 1),
+        ParameterCount::Less(n) => format!("::{}", parameter).repeat(n.saturating_sub(1)),
+        ParameterCount::Rest => format!(":*:{}", parameter),
+    }
+}
+
+/// Generates case arms which complete parameters of an argument with its possible values,
+/// or according to its value hint
+fn values(config: &Config) -> String {
+    config
+        .args
+        .iter()
+        .filter_map(|arg| {
+            let completion = match (&arg.possible_values, arg.value_hint) {
+                (Some(values), _) => {
+                    let values: Vec<_> = values.iter().map(|value| quote(value)).collect();
+                    format!("compadd -- {}", values.join(" "))
+                }
+                (None, Some(ValueHint::Other)) => ":".to_string(),
+                (None, Some(hint)) => action(hint).to_string(),
+                (None, None) => return None,
+            };
+            Some(format!("        {}) {} ;;\n", quote(&arg.name), completion))
+        })
+        .collect()
+}
+
+/// Completion function for parameters with the value hint
+fn action(hint: ValueHint) -> &'static str {
+    match hint {
+        ValueHint::FilePath => "_files",
+        ValueHint::DirPath => "_files -/",
+        ValueHint::Executable => "_command_names -e",
+        ValueHint::Hostname => "_hosts",
+        ValueHint::Username => "_users",
+        ValueHint::Url => "_urls",
+        ValueHint::Other => "",
+    }
+}
+
+/// Escapes backslashes and the special characters with a backslash
+fn escape(s: &str, special: &str) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        if c == '\\' || special.contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ConfigBuilder;
+
+    #[test]
+    fn specs_follow_parameter_counts() {
+        let config = ConfigBuilder::default()
+            .add_option("out".into(), 'o', "out".into(), ParameterCount::Exact(2))
+            .add_short_flag("verbose".into(), 'v')
+            .add_long_option("color".into(), "color".into(), ParameterCount::Less(2))
+            .add_long_option("exec".into(), "exec".into(), ParameterCount::Rest)
+            .add_long_option("host".into(), "host".into(), ParameterCount::Exact(1))
+            .set_help("out", "Write to [FILE]\nSecond line".into())
+            .set_value_name("out", "FILE".into())
+            .set_help("verbose", "Use verbose output".into())
+            .set_possible_values("color", vec!["auto".into(), "no color".into()])
+            .set_value_hint("exec", ValueHint::Executable)
+            .set_value_hint("host", ValueHint::Hostname)
+            .build();
+
+        assert_eq!(
+            "        '*--out[Write to [FILE\\]]:FILE:_files:FILE:_files' \\\n\
+             \x20       '*-o[Write to [FILE\\]]:FILE:_files:FILE:_files' \\\n\
+             \x20       '(-v)-v[Use verbose output]' \\\n\
+             \x20       '*--color::COLOR:(auto no\\ color)' \\\n\
+             \x20       '*--exec:*:CMD:_command_names -e' \\\n\
+             \x20       '*--host:HOST:_hosts' \\\n\
+             \x20       '(- *)--help[Prints help information]' \\\n\
+             \x20       '(- *)-h[Prints help information]' \\\n",
+            specs(&config)
+        );
+    }
+
+    #[test]
+    fn specs_exclude_groups() {
+        let config = ConfigBuilder::default()
+            .add_flag("json".into(), 'j', "json".into())
+            .add_long_flag("yaml".into(), "yaml".into())
+            .add_long_option("format".into(), "format".into(), ParameterCount::Exact(1))
+            .add_subcommand("build".into(), ParameterCount::Zero)
+            .add_group("output".into(), vec!["json".into(), "yaml".into(), "format".into(), "build".into()])
+            .build();
+
+        assert_eq!(
+            "        '(--json -j --yaml --format)--json' \\\n\
+             \x20       '(--json -j --yaml --format)-j' \\\n\
+             \x20       '(--yaml --json -j --format)--yaml' \\\n\
+             \x20       '(--json -j --yaml)*--format:FORMAT:_files' \\\n\
+             \x20       '(- *)--help[Prints help information]' \\\n\
+             \x20       '(- *)-h[Prints help information]' \\\n",
+            specs(&config)
+        );
+    }
+
+    #[test]
+    fn script_describes_subcommands() {
+        let config = ConfigBuilder::default()
+            .add_subcommand("build".into(), ParameterCount::Exact(1))
+            .add_subcommand("it's".into(), ParameterCount::Zero)
+            .set_help("build", "Builds the targets".into())
+            .set_possible_values("build", vec!["debug".into(), "release".into()])
+            .set_value_hint("it's", ValueHint::Other)
+            .build();
+
+        let script = script(&config, "my-prog");
+
+        assert!(script.starts_with("#compdef my-prog\n"));
+        assert!(script.contains("commands=('build:Builds the targets' 'it'\\''s:')"));
+        assert!(script.contains("'build') compadd -- 'debug' 'release' ;;"));
+        assert!(script.contains("'it'\\''s') : ;;"));
+        assert!(script.contains("'build') arg='build'; remaining=1 ;;"));
+        assert!(script.contains("    compdef _my_prog my-prog\n"));
+    }
+}
@@ -1,9 +1,21 @@
-use crate::arg::{ Arg, ArgType, ParameterCount };
+use crate::arg::{ Arg, ArgType, Builtin, Completer, ParameterCount, Validator, ValueHint };
 
 /// Builds Config
 #[derive(Debug, Default)]
 pub struct ConfigBuilder {
     args: Vec<Arg>,
+    pass_through: bool,
+    near_miss_parameters: bool,
+    parameter_count: Option<ParameterCount>,
+    parameter_name: Option<String>,
+    version: Option<String>,
+    help_width: Option<usize>,
+    help_template: Option<String>,
+    before_help: Option<String>,
+    after_help: Option<String>,
+    help_sections: Vec<(String, String)>,
+    examples: Vec<(String, String)>,
+    groups: Vec<(String, Vec<String>)>,
 }
 
 impl ConfigBuilder {
@@ -15,6 +27,7 @@ impl ConfigBuilder {
             name,
             kind: ArgType::Option,
             parameter_count,
+            ..Default::default()
         });
 
         self
@@ -28,6 +41,7 @@ impl ConfigBuilder {
             name,
             kind: ArgType::Option,
             parameter_count,
+            ..Default::default()
         });
 
         self
@@ -41,6 +55,7 @@ impl ConfigBuilder {
             name,
             kind: ArgType::Option,
             parameter_count,
+            ..Default::default()
         });
 
         self
@@ -54,6 +69,7 @@ impl ConfigBuilder {
             name,
             kind: ArgType::Option,
             parameter_count: ParameterCount::Zero,
+            ..Default::default()
         });
 
         self
@@ -67,6 +83,7 @@ impl ConfigBuilder {
             name,
             kind: ArgType::Option,
             parameter_count: ParameterCount::Zero,
+            ..Default::default()
         });
 
         self
@@ -80,6 +97,7 @@ impl ConfigBuilder {
             name,
             kind: ArgType::Option,
             parameter_count: ParameterCount::Zero,
+            ..Default::default()
         });
 
         self
@@ -93,15 +111,320 @@ impl ConfigBuilder {
             name,
             kind: ArgType::Subcommand,
             parameter_count,
+            ..Default::default()
         });
 
         self
     }
 
+    /// Attaches a validator to the argument with the name
+    ///
+    /// The validator is run on every parameter of the argument as it is consumed and
+    /// rejects it by returning ```Err``` with a message
+    /// # Panics
+    /// If no argument with the name has been added
+    pub fn set_validator<F>(mut self, name: &str, validator: F) -> Self
+    where
+        F: Fn(&str) -> Result<(), String> + 'static,
+    {
+        self.arg_mut(name).validator = Some(Validator::new(validator));
+
+        self
+    }
+
+    /// Sets a delimiter on which parameters of the argument with the name are split
+    ///
+    /// With ```','``` as the delimiter ```--tags a,b,c``` is the same as ```--tags a b c```,
+    /// a delimiter preceded by '\\' is not split on. The number of values after splitting
+    /// has to satisfy the ```ParameterCount``` of the argument
+    /// # Panics
+    /// If no argument with the name has been added
+    pub fn set_delimiter(mut self, name: &str, delimiter: char) -> Self {
+        self.arg_mut(name).delimiter = Some(delimiter);
+
+        self
+    }
+
+    /// Makes every parameter of the argument with the name a ```key=value``` pair
+    ///
+    /// Parameters are split on the first '=' and can be read with ```key_values```.
+    /// A parameter without '=' or with an empty key is an error. If a key is given more
+    /// than once the last value wins, every parameter is still kept in ```Args```.
+    /// A short option of the argument can have its parameter attached, as in ```-Dkey=value```
+    /// # Panics
+    /// If no argument with the name has been added
+    pub fn set_key_value(mut self, name: &str) -> Self {
+        self.arg_mut(name).key_value = true;
+
+        self
+    }
+
+    /// Restricts parameters of the argument with the name to the values
+    ///
+    /// The values are also offered by shell completion
+    /// # Panics
+    /// If no argument with the name has been added
+    pub fn set_possible_values(mut self, name: &str, values: Vec<String>) -> Self {
+        self.arg_mut(name).possible_values = Some(values);
+
+        self
+    }
+
+    /// Reads the environment variable when the option with the name does not appear
+    ///
+    /// The value is taken as a single parameter, which is split on the delimiter and checked
+    /// like parameters on the command line are. A flag is set unless the value is empty,
+    /// ```0``` or ```false```
+    /// # Panics
+    /// If no argument with the name has been added
+    pub fn set_env(mut self, name: &str, var: String) -> Self {
+        self.arg_mut(name).env = Some(var);
+
+        self
+    }
+
+    /// Sets the parameters of the option with the name when it does not appear
+    /// and its environment variable is not set
+    ///
+    /// The parameters are taken as they are, without being checked
+    /// # Panics
+    /// If no argument with the name has been added
+    pub fn set_default(mut self, name: &str, values: Vec<String>) -> Self {
+        self.arg_mut(name).default = Some(values);
+
+        self
+    }
+
+    /// Attaches a completer to the argument with the name
+    ///
+    /// When completion is requested at runtime, the completer is asked for candidates for a
+    /// parameter of the argument instead of its possible values, with the word under the cursor.
+    /// Candidates which do not start with that word are dropped
+    /// # Panics
+    /// If no argument with the name has been added
+    pub fn set_completer<F>(mut self, name: &str, completer: F) -> Self
+    where
+        F: Fn(&str) -> Vec<String> + 'static,
+    {
+        self.arg_mut(name).completer = Some(Completer::new(completer));
+
+        self
+    }
+
+    /// Sets the description of the argument with the name which is shown in help
+    /// # Panics
+    /// If no argument with the name has been added
+    pub fn set_help(mut self, name: &str, help: String) -> Self {
+        self.arg_mut(name).help = Some(help);
+
+        self
+    }
+
+    /// Sets the long description of the argument with the name which is shown in long help
+    /// for ```--help```, instead of the description which is shown for ```-h```
+    /// # Panics
+    /// If no argument with the name has been added
+    pub fn set_long_help(mut self, name: &str, long_help: String) -> Self {
+        self.arg_mut(name).long_help = Some(long_help);
+
+        self
+    }
+
+    /// Sets the name of a parameter of the argument with the name which is shown in help
+    /// # Panics
+    /// If no argument with the name has been added
+    pub fn set_value_name(mut self, name: &str, value_name: String) -> Self {
+        self.arg_mut(name).value_name = Some(value_name);
+
+        self
+    }
+
+    /// Sets the kind of value parameters of the argument with the name are
+    ///
+    /// Shell completion completes parameters according to the hint, unless the argument has
+    /// possible values, and help names them after it unless the argument has a value name
+    /// # Panics
+    /// If no argument with the name has been added
+    pub fn set_value_hint(mut self, name: &str, hint: ValueHint) -> Self {
+        self.arg_mut(name).value_hint = Some(hint);
+
+        self
+    }
+
+    /// Sets the heading of the help section the argument with the name is listed under
+    /// # Panics
+    /// If no argument with the name has been added
+    pub fn set_heading(mut self, name: &str, heading: String) -> Self {
+        self.arg_mut(name).heading = Some(heading);
+
+        self
+    }
+
+    /// Sets whether unknown options are collected instead of being an error
+    ///
+    /// Unknown options and the parameters that follow them are kept in order and
+    /// can be read with ```external```
+    pub fn set_pass_through(mut self, pass_through: bool) -> Self {
+        self.pass_through = pass_through;
+
+        self
+    }
+
+    /// Sets whether parameters one edit away from the name of a subcommand are taken as
+    /// parameters of the program
+    ///
+    /// By default they are an unknown subcommand with the subcommand as a suggestion,
+    /// which is a typo more often than not
+    pub fn set_near_miss_parameters(mut self, near_miss_parameters: bool) -> Self {
+        self.near_miss_parameters = near_miss_parameters;
+
+        self
+    }
+
+    /// Sets the number of parameters the program itself takes, which are the ones not
+    /// attributed to any option or subcommand
+    ///
+    /// Without it the program takes any number of them. A parameter the program does not
+    /// take any more of is an unknown subcommand
+    pub fn set_parameter_count(mut self, parameter_count: ParameterCount) -> Self {
+        self.parameter_count = Some(parameter_count);
+
+        self
+    }
+
+    /// Sets the name of a parameter of the program itself which is shown in usage,
+    /// instead of ```ARG```
+    pub fn set_parameter_name(mut self, parameter_name: String) -> Self {
+        self.parameter_name = Some(parameter_name);
+
+        self
+    }
+
+    /// Sets the version of the program, which adds a ```-V/--version``` flag that reports it
+    pub fn set_version(mut self, version: String) -> Self {
+        self.version = Some(version);
+
+        self
+    }
+
+    /// Sets the width help is wrapped to, instead of the ```COLUMNS``` environment variable
+    pub fn set_help_width(mut self, width: usize) -> Self {
+        self.help_width = Some(width);
+
+        self
+    }
+
+    /// Sets the template help is laid out by
+    ///
+    /// Tags like ```{usage}```, ```{options}```, ```{subcommands}```, ```{sections}```,
+    /// ```{section:Heading}```, ```{examples}```, ```{before}``` and ```{after}``` are replaced
+    /// with the parts of help they name, any other text is kept as is.
+    /// Literal braces are written as ```{{``` and ```}}```
+    pub fn set_help_template(mut self, template: String) -> Self {
+        self.help_template = Some(template);
+
+        self
+    }
+
+    /// Sets the text shown before help
+    pub fn set_before_help(mut self, text: String) -> Self {
+        self.before_help = Some(text);
+
+        self
+    }
+
+    /// Sets the text shown after help
+    pub fn set_after_help(mut self, text: String) -> Self {
+        self.after_help = Some(text);
+
+        self
+    }
+
+    /// Adds a section with a heading and a body of text to help
+    pub fn add_help_section(mut self, heading: String, body: String) -> Self {
+        self.help_sections.push((heading, body));
+
+        self
+    }
+
+    /// Adds an example invocation with its description to help
+    pub fn add_example(mut self, command: String, description: String) -> Self {
+        self.examples.push((command, description));
+
+        self
+    }
+
+    /// Adds a group of arguments of which at most one may appear on the command line
+    ///
+    /// Parsing fails with ```ErrorKind::Conflict``` for every argument of the group
+    /// which appears after another one of it did
+    /// # Note
+    /// Parameters read from an environment variable or a default do not count as appearing
+    /// # Panics
+    /// If no argument with one of the names has been added
+    pub fn add_group(mut self, name: String, args: Vec<String>) -> Self {
+        for arg in &args {
+            self.arg_mut(arg);
+        }
+        self.groups.push((name, args));
+
+        self
+    }
+
+    fn add_builtin(&mut self, builtin: Builtin, name: &str, short: char, help: &str) {
+        if self.args.iter().any(|arg| arg.name == name) {
+            return;
+        }
+
+        let short = Some(short).filter(|short| self.args.iter().all(|arg| arg.short != Some(*short)));
+        let long = Some(name.to_string())
+            .filter(|long| self.args.iter().all(|arg| arg.long.as_ref() != Some(long)));
+
+        if short.is_some() || long.is_some() {
+            self.args.push(Arg {
+                short,
+                long,
+                name: name.to_string(),
+                kind: ArgType::Option,
+                help: Some(help.to_string()),
+                builtin: Some(builtin),
+                ..Default::default()
+            });
+        }
+    }
+
+    fn arg_mut(&mut self, name: &str) -> &mut Arg {
+        self.args
+            .iter_mut()
+            .find(|arg| arg.name == name)
+            .unwrap_or_else(|| panic!("No argument with name {} has been added", name))
+    }
+
     /// Builds ```Config```
-    pub fn build(self) -> Config {
+    /// # Note
+    /// A ```-h/--help``` flag, and a ```-V/--version``` flag if the version is set, are added
+    /// without the short or the long version if another option already uses it. They are not
+    /// added at all if an argument is already named ```help``` or ```version```
+    pub fn build(mut self) -> Config {
+        self.add_builtin(Builtin::Help, "help", 'h', "Prints help information");
+        if self.version.is_some() {
+            self.add_builtin(Builtin::Version, "version", 'V', "Prints version information");
+        }
+
         Config {
             args: self.args,
+            pass_through: self.pass_through,
+            near_miss_parameters: self.near_miss_parameters,
+            parameter_count: self.parameter_count,
+            parameter_name: self.parameter_name,
+            version: self.version,
+            help_width: self.help_width,
+            help_template: self.help_template,
+            before_help: self.before_help,
+            after_help: self.after_help,
+            help_sections: self.help_sections,
+            examples: self.examples,
+            groups: self.groups,
         }
     }
 }
@@ -109,4 +432,16 @@ impl ConfigBuilder {
 /// Represents Configuration for CLI Arguments
 pub struct Config {
     pub args: Vec<Arg>,
+    pub pass_through: bool,
+    pub near_miss_parameters: bool,
+    pub parameter_count: Option<ParameterCount>,
+    pub parameter_name: Option<String>,
+    pub version: Option<String>,
+    pub help_width: Option<usize>,
+    pub help_template: Option<String>,
+    pub before_help: Option<String>,
+    pub after_help: Option<String>,
+    pub help_sections: Vec<(String, String)>,
+    pub examples: Vec<(String, String)>,
+    pub groups: Vec<(String, Vec<String>)>,
 }
\ No newline at end of file
@@ -0,0 +1,463 @@
+mod template;
+mod usage;
+mod width;
+
+use crate::arg::{Arg, ArgType, ParameterCount};
+use crate::config::Config;
+pub use usage::usage;
+pub(crate) use width::display_width;
+use width::help_width;
+
+/// Widest first column which still has descriptions next to it
+const MAX_COLUMN: usize = 30;
+/// Indentation of descriptions in long help
+const LONG_INDENT: usize = 10;
+
+/// Generates short help for a program from its Config, which is shown for ```-h```
+/// # Returns
+/// Help laid out by the help template, where every argument has the first line of its description
+/// # Note
+/// Without a template, help is the usage line followed by a table for options, one for every
+/// custom heading and one for subcommands, then custom sections, examples and the text after help
+pub fn help(config: &Config, program_name: &str) -> String {
+    template::render(config, program_name, false)
+}
+
+/// Generates long help for a program from its Config, which is shown for ```--help```
+/// # Returns
+/// Same as ```help```, but every argument is followed by its whole long description,
+/// or its description if it has no long one
+pub fn long_help(config: &Config, program_name: &str) -> String {
+    template::render(config, program_name, true)
+}
+
+/// Heading and the arguments listed under it
+pub(crate) type Section<'a> = (String, Vec<&'a Arg>);
+
+/// Renders tables of arguments under their headings
+fn tables(sections: &[Section], config: &Config, long: bool) -> String {
+    let width = help_width(config);
+    let column = column(&config.args);
+    let mut tables = String::new();
+
+    for (heading, args) in sections {
+        tables.push_str(&format!("\n{}:\n", heading));
+        if long {
+            tables.push_str(&long_table(args, width));
+        } else {
+            tables.push_str(&table(args, column, width));
+        }
+    }
+
+    tables
+}
+
+/// Groups arguments by the heading they are listed under
+/// # Returns
+/// Sections of options without a heading and of arguments with custom headings,
+/// followed by the section of subcommands without a heading
+pub(crate) fn sections(config: &Config) -> (Vec<Section<'_>>, Vec<Section<'_>>) {
+    let mut custom: Vec<Section> = vec![];
+    let mut options = vec![];
+    let mut subcommands = vec![];
+
+    for arg in &config.args {
+        match (&arg.heading, &arg.kind) {
+            (Some(heading), _) => match custom.iter_mut().find(|(h, _)| h == heading) {
+                Some((_, args)) => args.push(arg),
+                None => custom.push((heading.clone(), vec![arg])),
+            },
+            (None, ArgType::Option) => options.push(arg),
+            (None, ArgType::Subcommand) => subcommands.push(arg),
+        }
+    }
+
+    let mut option_sections = vec![];
+    if !options.is_empty() {
+        option_sections.push(("Options".to_string(), options));
+    }
+    option_sections.extend(custom);
+
+    let mut subcommand_sections = vec![];
+    if !subcommands.is_empty() {
+        subcommand_sections.push(("Commands".to_string(), subcommands));
+    }
+
+    (option_sections, subcommand_sections)
+}
+
+/// Finds the column descriptions start at, so that they are aligned across every table
+fn column(args: &[Arg]) -> usize {
+    args.iter()
+        .map(|arg| display_width(&signature(arg)))
+        .filter(|width| *width <= MAX_COLUMN)
+        .max()
+        .unwrap_or(0)
+        + 4
+}
+
+fn table(args: &[&Arg], column: usize, width: usize) -> String {
+    let rows: Vec<_> = args
+        .iter()
+        .map(|arg| {
+            let help = arg.help.as_deref().unwrap_or_default();
+            (signature(arg), help.lines().next().unwrap_or_default().to_string())
+        })
+        .collect();
+
+    let mut table = String::new();
+    for (signature, help) in rows {
+        let mut line = format!("  {}", signature);
+        let mut lines = wrap(&help, width.saturating_sub(column).max(1)).into_iter();
+
+        if let Some(first) = lines.next() {
+            if display_width(&line) + 2 > column {
+                table.push_str(&line);
+                table.push('\n');
+                line = String::new();
+            }
+            let padding = column - display_width(&line);
+            line.push_str(&" ".repeat(padding));
+            line.push_str(&first);
+        }
+        table.push_str(&line);
+        table.push('\n');
+
+        for rest in lines {
+            table.push_str(&" ".repeat(column));
+            table.push_str(&rest);
+            table.push('\n');
+        }
+    }
+
+    table
+}
+
+fn long_table(args: &[&Arg], width: usize) -> String {
+    let mut table = String::new();
+
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            table.push('\n');
+        }
+        table.push_str(&format!("  {}\n", signature(arg)));
+
+        let mut help = arg.long_help.as_ref().or(arg.help.as_ref()).cloned().unwrap_or_default();
+        let fallbacks = fallbacks(arg);
+        if !fallbacks.is_empty() {
+            if !help.is_empty() {
+                help.push('\n');
+            }
+            help.push_str(&fallbacks);
+        }
+        for line in wrap(&help, width.saturating_sub(LONG_INDENT).max(1)) {
+            if !line.is_empty() {
+                table.push_str(&" ".repeat(LONG_INDENT));
+                table.push_str(&line);
+            }
+            table.push('\n');
+        }
+    }
+
+    table
+}
+
+/// Describes where the parameters of an argument come from when it does not appear,
+/// as in ```[env: OUT] [default: a.txt]```
+pub(crate) fn fallbacks(arg: &Arg) -> String {
+    let mut fallbacks = vec![];
+
+    if let Some(env) = &arg.env {
+        fallbacks.push(format!("[env: {}]", env));
+    }
+    if let Some(default) = &arg.default {
+        fallbacks.push(format!("[default: {}]", default.join(", ")));
+    }
+
+    fallbacks.join(" ")
+}
+
+/// Describes how an argument is written, as in ```-o, --out <FILE>```
+pub(crate) fn signature(arg: &Arg) -> String {
+    let mut signature = match (&arg.kind, arg.short, &arg.long) {
+        (ArgType::Subcommand, _, _) => arg.name.clone(),
+        (ArgType::Option, Some(short), Some(long)) => format!("-{}, --{}", short, long),
+        (ArgType::Option, Some(short), None) => format!("-{}", short),
+        (ArgType::Option, None, Some(long)) => format!("    --{}", long),
+        (ArgType::Option, None, None) => arg.name.clone(),
+    };
+
+    let placeholders = placeholders(arg);
+    if !placeholders.is_empty() {
+        signature.push(' ');
+        signature.push_str(&placeholders);
+    }
+
+    signature
+}
+
+/// Describes the parameters of an argument, as in ```<FILE> <FILE>...```
+pub(crate) fn placeholders(arg: &Arg) -> String {
+    count_placeholders(&arg.value_name(), arg.parameter_count)
+}
+
+/// Describes a number of parameters with the name, as in ```<FILE> <FILE>...```
+pub(crate) fn count_placeholders(value_name: &str, parameter_count: ParameterCount) -> String {
+    let value = format!("<{}>", value_name);
+
+    match parameter_count {
+        ParameterCount::Zero => String::new(),
+        ParameterCount::Exact(n) => vec![value; n].join(" "),
+        ParameterCount::More(n) => format!("{}...", vec![value; n + 1].join(" ")),
+        ParameterCount::Less(n) if n <= 1 => String::new(),
+        ParameterCount::Less(2) => format!("[{}]", value),
+        ParameterCount::Less(_) | ParameterCount::Rest => format!("[{}...]", value),
+    }
+}
+
+/// Wraps text into lines no wider than width, keeping the line breaks it already has
+/// # Note
+/// Words wider than width are not broken up
+pub(crate) fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && display_width(&line) + 1 + display_width(word) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{ConfigBuilder, ValueHint};
+
+    #[test]
+    fn generated_help() {
+        let config = ConfigBuilder::default()
+            .add_flag("verbose".into(), 'v', "verbose".into())
+            .add_long_option("out".into(), "out".into(), ParameterCount::Exact(1))
+            .add_short_option("jobs".into(), 'j', ParameterCount::Less(2))
+            .add_subcommand("build".into(), ParameterCount::More(0))
+            .set_help("verbose", "Use verbose output".into())
+            .set_help("out", "Write the result to this file instead of printing it, the file is created if it does not exist".into())
+            .set_value_name("out", "FILE".into())
+            .set_heading("jobs", "Performance".into())
+            .set_help("build", "Builds the targets".into())
+            .set_help_width(80)
+            .build();
+
+        assert_eq!(
+            "Usage: prog [-v] [--out <FILE>] [-j [<JOBS>]] [build <BUILD>...] [<ARG>...]\n\
+             \n\
+             Options:\n\
+             \x20 -v, --verbose     Use verbose output\n\
+             \x20     --out <FILE>  Write the result to this file instead of printing it, the\n\
+             \x20                   file is created if it does not exist\n\
+             \x20 -h, --help        Prints help information\n\
+             \n\
+             Performance:\n\
+             \x20 -j [<JOBS>]\n\
+             \n\
+             Commands:\n\
+             \x20 build <BUILD>...  Builds the targets\n",
+            help(&config, "prog")
+        );
+    }
+
+    #[test]
+    fn generated_long_help() {
+        let config = ConfigBuilder::default()
+            .add_flag("verbose".into(), 'v', "verbose".into())
+            .add_subcommand("build".into(), ParameterCount::Zero)
+            .set_help("verbose", "Use verbose output\nSecond line is only in long help".into())
+            .set_long_help("verbose", "Use verbose output\n\nExample: prog -v build".into())
+            .set_help("build", "Builds the targets".into())
+            .set_help_width(80)
+            .build();
+
+        assert_eq!(
+            "Usage: prog [-v] [build] [<ARG>...]\n\
+             \n\
+             Options:\n\
+             \x20 -v, --verbose  Use verbose output\n\
+             \x20 -h, --help     Prints help information\n\
+             \n\
+             Commands:\n\
+             \x20 build          Builds the targets\n",
+            help(&config, "prog")
+        );
+        assert_eq!(
+            "Usage: prog [-v] [build] [<ARG>...]\n\
+             \n\
+             Options:\n\
+             \x20 -v, --verbose\n\
+             \x20         Use verbose output\n\
+             \n\
+             \x20         Example: prog -v build\n\
+             \n\
+             \x20 -h, --help\n\
+             \x20         Prints help information\n\
+             \n\
+             Commands:\n\
+             \x20 build\n\
+             \x20         Builds the targets\n",
+            long_help(&config, "prog")
+        );
+    }
+
+    #[test]
+    fn long_help_shows_fallbacks() {
+        let config = ConfigBuilder::default()
+            .add_long_option("out".into(), "out".into(), ParameterCount::Exact(1))
+            .add_long_option("tags".into(), "tags".into(), ParameterCount::More(0))
+            .set_help("out", "Write to the file".into())
+            .set_env("out", "PROG_OUT".into())
+            .set_default("out", vec!["a.txt".into()])
+            .set_default("tags", vec!["x".into(), "y".into()])
+            .set_help_width(80)
+            .build();
+
+        assert_eq!(
+            "Usage: prog [--out <OUT>] [--tags <TAGS>...] [<ARG>...]\n\
+             \n\
+             Options:\n\
+             \x20     --out <OUT>\n\
+             \x20         Write to the file\n\
+             \x20         [env: PROG_OUT] [default: a.txt]\n\
+             \n\
+             \x20     --tags <TAGS>...\n\
+             \x20         [default: x, y]\n\
+             \n\
+             \x20 -h, --help\n\
+             \x20         Prints help information\n",
+            long_help(&config, "prog")
+        );
+    }
+
+    #[test]
+    fn placeholders_named_after_value_hints() {
+        let config = ConfigBuilder::default()
+            .add_option("input".into(), 'i', "input".into(), ParameterCount::Exact(1))
+            .add_long_option("dirs".into(), "dirs".into(), ParameterCount::More(0))
+            .add_long_option("remote".into(), "remote".into(), ParameterCount::Exact(1))
+            .add_long_option("token".into(), "token".into(), ParameterCount::Exact(1))
+            .set_value_hint("input", ValueHint::FilePath)
+            .set_value_hint("dirs", ValueHint::DirPath)
+            .set_value_hint("remote", ValueHint::Url)
+            .set_value_name("remote", "REMOTE".into())
+            .set_value_hint("token", ValueHint::Other)
+            .build();
+
+        let signatures: Vec<_> = config.args.iter().map(signature).collect();
+
+        assert_eq!(
+            vec![
+                "-i, --input <FILE>",
+                "    --dirs <DIR>...",
+                "    --remote <REMOTE>",
+                "    --token <TOKEN>",
+                "-h, --help",
+            ],
+            signatures
+        );
+    }
+
+    #[test]
+    fn wrapped_to_width() {
+        let config = ConfigBuilder::default()
+            .add_long_flag("名前".into(), "名前".into())
+            .add_short_flag("all".into(), 'a')
+            .set_help("名前", "日本語 の 説明 です".into())
+            .set_help("all", "Show every entry, including hidden ones".into())
+            .set_help_width(30)
+            .build();
+
+        assert_eq!(
+            "Usage: prog [--名前] [-a] [<ARG>...]\n\
+             \n\
+             Options:\n\
+             \x20     --名前  日本語 の 説明\n\
+             \x20             です\n\
+             \x20 -a          Show every\n\
+             \x20             entry, including\n\
+             \x20             hidden ones\n\
+             \x20 -h, --help  Prints help\n\
+             \x20             information\n",
+            help(&config, "prog")
+        );
+    }
+
+    #[test]
+    fn custom_template() {
+        let config = ConfigBuilder::default()
+            .add_short_flag("verbose".into(), 'v')
+            .add_subcommand("build".into(), ParameterCount::Zero)
+            .set_help("verbose", "Use verbose output".into())
+            .set_before_help("prog builds things".into())
+            .set_after_help("Report bugs to the issue tracker".into())
+            .add_example("prog -v build".into(), "Builds verbosely".into())
+            .add_help_section("Exit status".into(), "0 on success, 1 on failure".into())
+            .set_help_template("{before}\n\n{subcommands}\n\n{options}\n\n{section:Exit status}\n\n{examples}\n\nUsage: {usage}\n{after}\n{unknown}".into())
+            .set_help_width(80)
+            .build();
+
+        assert_eq!(
+            "prog builds things\n\
+             \n\
+             Commands:\n\
+             \x20 build\n\
+             \n\
+             Options:\n\
+             \x20 -v          Use verbose output\n\
+             \x20 -h, --help  Prints help information\n\
+             \n\
+             Exit status:\n\
+             \x20 0 on success, 1 on failure\n\
+             \n\
+             Examples:\n\
+             \x20 prog -v build\n\
+             \x20     Builds verbosely\n\
+             \n\
+             Usage: prog [-v] [build] [<ARG>...]\n\
+             Report bugs to the issue tracker\n\
+             {unknown}\n",
+            help(&config, "prog")
+        );
+    }
+
+    #[test]
+    fn template_keeps_blank_lines_and_braces() {
+        let config = ConfigBuilder::default()
+            .set_before_help("prog builds things\n\n\nfrom sources".into())
+            .set_help_template("{before}\n\n\nUsage: {usage}\n\n{examples}\n\n{after}\n\nUse {{name}} or {{}}, not {name}\nKeep { this and {this}} too}\n".into())
+            .set_help_width(80)
+            .build();
+
+        assert_eq!(
+            "prog builds things\n\
+             \n\
+             \n\
+             from sources\n\
+             \n\
+             \n\
+             Usage: prog [<ARG>...]\n\
+             \n\
+             Use {name} or {}, not {name}\n\
+             Keep { this and {this}} too}\n",
+            help(&config, "prog")
+        );
+    }
+}
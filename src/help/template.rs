@@ -0,0 +1,158 @@
+use crate::config::Config;
+
+use super::width::help_width;
+use super::{sections, tables, usage, wrap};
+
+/// Stands in for a tag that rendered to nothing until its line is removed
+const EMPTY_TAG: &str = "\u{0}";
+
+/// Template used when the Config does not have one
+const DEFAULT_TEMPLATE: &str = "{before}\n\nUsage: {usage}\n\n{options}\n\n{subcommands}\n\n{sections}\n\n{examples}\n\n{after}";
+
+/// Renders the help template of a Config
+///
+/// These tags are replaced, any other text is kept as is:
+/// - ```{before}``` and ```{after}```, text before and after help
+/// - ```{usage}```, the usage line without a heading
+/// - ```{options}```, tables of options and of arguments with custom headings
+/// - ```{subcommands}```, table of subcommands
+/// - ```{sections}```, every custom section
+/// - ```{section:Heading}```, the custom section with the heading
+/// - ```{examples}```, example invocations
+///
+/// ```{{``` and ```}}``` are written as ```{``` and ```}```, a ```{``` without a closing ```}```
+/// before the next ```{``` is kept as is
+/// # Note
+/// A line with nothing but a tag that renders to nothing is removed together with an empty
+/// line next to it, so that sections stay separated by the empty lines of the template
+pub fn render(config: &Config, program_name: &str, long: bool) -> String {
+    let template = config.help_template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+    let mut rendered = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        rendered.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(brace) = ["{{", "}}", "}"].iter().find(|brace| rest.starts_with(*brace)) {
+            rendered.push_str(&brace[..1]);
+            rest = &rest[brace.len()..];
+            continue;
+        }
+
+        let end = match rest[1..].find(['{', '}']) {
+            Some(end) if rest[1 + end..].starts_with('}') => 1 + end,
+            _ => {
+                rendered.push('{');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        match tag(&rest[1..end], config, program_name, long) {
+            Some(text) if text.trim().is_empty() => rendered.push_str(EMPTY_TAG),
+            Some(text) => rendered.push_str(text.trim_matches('\n')),
+            None => rendered.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    rendered.push_str(rest);
+
+    remove_empty_tags(&rendered)
+}
+
+fn tag(tag: &str, config: &Config, program_name: &str, long: bool) -> Option<String> {
+    let width = help_width(config);
+    let text = match tag {
+        "before" => wrap(config.before_help.as_deref().unwrap_or_default(), width).join("\n"),
+        "after" => wrap(config.after_help.as_deref().unwrap_or_default(), width).join("\n"),
+        "usage" => usage(config, program_name),
+        "options" => tables(&sections(config).0, config, long),
+        "subcommands" => tables(&sections(config).1, config, long),
+        "sections" => config
+            .help_sections
+            .iter()
+            .map(|(heading, body)| section(heading, body, width))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        "examples" => examples(config, width),
+        _ => {
+            let heading = tag.strip_prefix("section:")?;
+            let (heading, body) = config.help_sections.iter().find(|(h, _)| h == heading)?;
+            section(heading, body, width)
+        }
+    };
+
+    Some(text)
+}
+
+fn section(heading: &str, body: &str, width: usize) -> String {
+    let mut section = format!("{}:", heading);
+
+    for line in wrap(body, width.saturating_sub(2).max(1)) {
+        section.push('\n');
+        if !line.is_empty() {
+            section.push_str("  ");
+            section.push_str(&line);
+        }
+    }
+
+    section
+}
+
+fn examples(config: &Config, width: usize) -> String {
+    if config.examples.is_empty() {
+        return String::new();
+    }
+
+    let mut examples = "Examples:".to_string();
+    for (command, description) in &config.examples {
+        examples.push_str(&format!("\n  {}", command));
+        for line in wrap(description, width.saturating_sub(6).max(1)) {
+            examples.push('\n');
+            if !line.is_empty() {
+                examples.push_str("      ");
+                examples.push_str(&line);
+            }
+        }
+    }
+
+    examples
+}
+
+/// Removes lines with nothing but a tag that rendered to nothing, each with an empty line
+/// after it or else before it, and empty lines at the start and the end of the text
+fn remove_empty_tags(text: &str) -> String {
+    let mut lines: Vec<&str> = vec![];
+    let mut removed = false;
+
+    for line in text.lines() {
+        if line.trim() == EMPTY_TAG {
+            removed = true;
+            continue;
+        }
+        if removed {
+            removed = false;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if lines.last().is_some_and(|last| last.trim().is_empty()) {
+                lines.pop();
+            }
+        }
+        lines.push(line);
+    }
+    if removed && lines.last().is_some_and(|last| last.trim().is_empty()) {
+        lines.pop();
+    }
+
+    let start = lines.iter().position(|line| !line.trim().is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !line.trim().is_empty()).map_or(start, |end| end + 1);
+
+    let mut text = String::new();
+    for line in &lines[start..end] {
+        text.push_str(&line.replace(EMPTY_TAG, ""));
+        text.push('\n');
+    }
+
+    text
+}
@@ -0,0 +1,92 @@
+use crate::arg::{Arg, ArgType, ParameterCount};
+use crate::config::Config;
+
+use super::{count_placeholders, placeholders};
+
+/// Generates a compact usage line for a program from its Config, as in
+/// ```prog [-v] [--out <FILE>] [build <TARGET>...] [<ARG>...]```
+/// # Note
+/// Every option and subcommand is optional, builtin options like ```--help``` are left out.
+/// The parameters of the program itself come last, named ```ARG``` unless a name is set
+pub fn usage(config: &Config, program_name: &str) -> String {
+    let mut usage = program_name.to_string();
+
+    for arg in config
+        .args
+        .iter()
+        .filter(|arg| arg.kind == ArgType::Option && arg.builtin.is_none())
+    {
+        let name = match (arg.short, &arg.long) {
+            (Some(short), _) => format!("-{}", short),
+            (None, Some(long)) => format!("--{}", long),
+            (None, None) => arg.name.clone(),
+        };
+        usage.push_str(&format!(" [{}]", signature(name, arg)));
+    }
+
+    for arg in config.args.iter().filter(|arg| arg.kind == ArgType::Subcommand) {
+        usage.push_str(&format!(" [{}]", signature(arg.name.clone(), arg)));
+    }
+
+    let parameter_name = config.parameter_name.as_deref().unwrap_or("ARG");
+    let placeholders = count_placeholders(parameter_name, config.parameter_count.unwrap_or(ParameterCount::Rest));
+    if !placeholders.is_empty() {
+        usage.push_str(&format!(" {}", placeholders));
+    }
+
+    usage
+}
+
+/// Name of an argument followed by its parameters
+fn signature(name: String, arg: &Arg) -> String {
+    let placeholders = placeholders(arg);
+
+    if placeholders.is_empty() {
+        name
+    } else {
+        format!("{} {}", name, placeholders)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{ConfigBuilder, ParameterCount};
+
+    #[test]
+    fn usage_line() {
+        let config = ConfigBuilder::default()
+            .add_short_flag("verbose".into(), 'v')
+            .add_long_option("out".into(), "out".into(), ParameterCount::Exact(1))
+            .add_option("src".into(), 's', "src".into(), ParameterCount::More(0))
+            .add_subcommand("build".into(), ParameterCount::Zero)
+            .add_subcommand("run".into(), ParameterCount::More(0))
+            .set_value_name("out", "FILE".into())
+            .set_value_name("run", "TARGET".into())
+            .set_version("1.0.0".into())
+            .build();
+
+        assert_eq!(
+            "prog [-v] [--out <FILE>] [-s <SRC>...] [build] [run <TARGET>...] [<ARG>...]",
+            usage(&config, "prog")
+        );
+    }
+
+    #[test]
+    fn program_parameters() {
+        let config = ConfigBuilder::default()
+            .add_short_flag("verbose".into(), 'v')
+            .set_parameter_count(ParameterCount::Exact(2))
+            .set_parameter_name("FILE".into())
+            .build();
+
+        assert_eq!("prog [-v] <FILE> <FILE>", usage(&config, "prog"));
+
+        let config = ConfigBuilder::default()
+            .set_parameter_count(ParameterCount::Zero)
+            .build();
+
+        assert_eq!("prog", usage(&config, "prog"));
+    }
+}
@@ -0,0 +1,86 @@
+use std::env;
+
+use crate::config::Config;
+
+/// Width help is wrapped to when neither an override nor ```COLUMNS``` is set
+const DEFAULT_WIDTH: usize = 80;
+
+/// Gets the width help is wrapped to, which is the override from the Config,
+/// then the ```COLUMNS``` environment variable and then 80
+pub fn help_width(config: &Config) -> usize {
+    config
+        .help_width
+        .or_else(|| env::var("COLUMNS").ok()?.trim().parse().ok())
+        .filter(|width| *width > 0)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// Gets the number of terminal columns a string takes up
+/// # Note
+/// East Asian wide characters and most emoji take up two columns,
+/// combining marks and control characters take up none
+pub fn display_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+fn char_width(c: char) -> usize {
+    let c = c as u32;
+
+    if c < 0x20 || (0x7f..0xa0).contains(&c) || is_zero_width(c) {
+        0
+    } else if is_wide(c) {
+        2
+    } else {
+        1
+    }
+}
+
+fn is_zero_width(c: u32) -> bool {
+    matches!(
+        c,
+        0x0300..=0x036f
+            | 0x0483..=0x0489
+            | 0x0591..=0x05bd
+            | 0x0610..=0x061a
+            | 0x064b..=0x065f
+            | 0x1ab0..=0x1aff
+            | 0x1dc0..=0x1dff
+            | 0x200b..=0x200f
+            | 0x20d0..=0x20ff
+            | 0xfe00..=0xfe0f
+            | 0xfe20..=0xfe2f
+    )
+}
+
+fn is_wide(c: u32) -> bool {
+    matches!(
+        c,
+        0x1100..=0x115f
+            | 0x2e80..=0x303e
+            | 0x3041..=0x33ff
+            | 0x3400..=0x4dbf
+            | 0x4e00..=0x9fff
+            | 0xa000..=0xa4cf
+            | 0xac00..=0xd7a3
+            | 0xf900..=0xfaff
+            | 0xfe30..=0xfe4f
+            | 0xff00..=0xff60
+            | 0xffe0..=0xffe6
+            | 0x1f300..=0x1f64f
+            | 0x1f900..=0x1f9ff
+            | 0x20000..=0x2fffd
+            | 0x30000..=0x3fffd
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn width_of_unicode() {
+        assert_eq!(5, display_width("hello"));
+        assert_eq!(4, display_width("日本"));
+        assert_eq!(4, display_width("cafe\u{301}"));
+    }
+}
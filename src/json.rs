@@ -0,0 +1,243 @@
+use crate::arg::{Arg, ParameterCount};
+use crate::config::Config;
+
+/// Generates a JSON description of the command line interface of a program from its Config
+///
+/// The description is an object with the program name, the version, whether unknown options
+/// are passed through, every argument with its kind, short and long names, parameter count,
+/// value name and hint, delimiter, possible values, environment variable, default, the groups
+/// it is in, help and heading, and every group with the names of its arguments
+/// # Note
+/// Parameter counts are objects with the least and the most parameters, where the most is null
+/// when unbounded, and whether the rest of the arguments are taken. Kinds, value hints and
+/// builtins are named after their variants
+pub fn json(config: &Config, program_name: &str) -> String {
+    let description = Json::Object(vec![
+        ("name", Json::from(program_name)),
+        ("version", Json::from(config.version.as_deref())),
+        ("pass_through", Json::Bool(config.pass_through)),
+        ("args", Json::Array(config.args.iter().map(|a| arg(config, a)).collect())),
+        (
+            "groups",
+            Json::Array(
+                config
+                    .groups
+                    .iter()
+                    .map(|(name, args)| {
+                        Json::Object(vec![("name", Json::from(name.as_str())), ("args", strings(args))])
+                    })
+                    .collect(),
+            ),
+        ),
+    ]);
+
+    let mut json = String::new();
+    description.write(&mut json, 0);
+    json.push('\n');
+    json
+}
+
+fn arg(config: &Config, arg: &Arg) -> Json {
+    let groups: Vec<_> = config
+        .groups
+        .iter()
+        .filter(|(_, args)| args.contains(&arg.name))
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    Json::Object(vec![
+        ("name", Json::from(arg.name.as_str())),
+        ("kind", Json::from(format!("{:?}", arg.kind).as_str())),
+        ("short", Json::from(arg.short.map(String::from).as_deref())),
+        ("long", Json::from(arg.long.as_deref())),
+        ("parameter_count", parameter_count(arg.parameter_count)),
+        ("value_name", Json::from(arg.value_name().as_str())),
+        ("value_hint", Json::from(arg.value_hint.map(|hint| format!("{:?}", hint)).as_deref())),
+        ("delimiter", Json::from(arg.delimiter.map(String::from).as_deref())),
+        ("key_value", Json::Bool(arg.key_value)),
+        ("possible_values", arg.possible_values.as_ref().map_or(Json::Null, |values| strings(values))),
+        ("env", Json::from(arg.env.as_deref())),
+        ("default", arg.default.as_ref().map_or(Json::Null, |values| strings(values))),
+        ("groups", strings(&groups)),
+        ("help", Json::from(arg.help.as_deref())),
+        ("long_help", Json::from(arg.long_help.as_deref())),
+        ("heading", Json::from(arg.heading.as_deref())),
+        ("builtin", Json::from(arg.builtin.map(|builtin| format!("{:?}", builtin)).as_deref())),
+    ])
+}
+
+fn strings(strings: &[String]) -> Json {
+    Json::Array(strings.iter().map(|s| Json::from(s.as_str())).collect())
+}
+
+fn parameter_count(parameter_count: ParameterCount) -> Json {
+    let (min, max) = match parameter_count {
+        ParameterCount::Zero => (0, Some(0)),
+        ParameterCount::Exact(n) => (n, Some(n)),
+        ParameterCount::More(n) => (n + 1, None),
+        ParameterCount::Less(n) => (0, Some(n.saturating_sub(1))),
+        ParameterCount::Rest => (0, None),
+    };
+
+    Json::Object(vec![
+        ("min", Json::Number(min)),
+        ("max", max.map_or(Json::Null, Json::Number)),
+        ("rest", Json::Bool(parameter_count == ParameterCount::Rest)),
+    ])
+}
+
+/// Represents a JSON value, which is written with two spaces of indentation per level
+enum Json {
+    Null,
+    Bool(bool),
+    Number(usize),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    fn write(&self, json: &mut String, indent: usize) {
+        match self {
+            Json::Null => json.push_str("null"),
+            Json::Bool(value) => json.push_str(&value.to_string()),
+            Json::Number(value) => json.push_str(&value.to_string()),
+            Json::String(value) => json.push_str(&quote(value)),
+            Json::Array(values) if values.is_empty() => json.push_str("[]"),
+            Json::Array(values) => {
+                json.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    json.push_str(if i == 0 { "\n" } else { ",\n" });
+                    json.push_str(&"  ".repeat(indent + 1));
+                    value.write(json, indent + 1);
+                }
+                json.push('\n');
+                json.push_str(&"  ".repeat(indent));
+                json.push(']');
+            }
+            Json::Object(members) => {
+                json.push('{');
+                for (i, (key, value)) in members.iter().enumerate() {
+                    json.push_str(if i == 0 { "\n" } else { ",\n" });
+                    json.push_str(&"  ".repeat(indent + 1));
+                    json.push_str(&format!("{}: ", quote(key)));
+                    value.write(json, indent + 1);
+                }
+                json.push('\n');
+                json.push_str(&"  ".repeat(indent));
+                json.push('}');
+            }
+        }
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_string())
+    }
+}
+
+impl From<Option<&str>> for Json {
+    fn from(value: Option<&str>) -> Self {
+        value.map_or(Json::Null, Json::from)
+    }
+}
+
+/// Quotes a string for JSON, escaping quotes, backslashes and control characters
+fn quote(s: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{ConfigBuilder, ValueHint};
+
+    #[test]
+    fn generated_json() {
+        let config = ConfigBuilder::default()
+            .add_option("out".into(), 'o', "out".into(), ParameterCount::More(0))
+            .add_subcommand("run".into(), ParameterCount::Rest)
+            .set_help("out", "Write to the \"file\"\nor C:\\dir".into())
+            .set_value_hint("out", ValueHint::FilePath)
+            .set_delimiter("out", ',')
+            .set_possible_values("out", vec!["a".into(), "b".into()])
+            .set_env("out", "PROG_OUT".into())
+            .set_default("out", vec!["a".into()])
+            .add_group("targets".into(), vec!["out".into(), "run".into()])
+            .set_version("1.2.3".into())
+            .build();
+
+        let json = json(&config, "prog");
+
+        assert!(json.starts_with(
+            "{\n  \"name\": \"prog\",\n  \"version\": \"1.2.3\",\n  \"pass_through\": false,\n  \"args\": [\n"
+        ));
+        assert!(json.contains(
+            "    {\n\
+             \x20     \"name\": \"out\",\n\
+             \x20     \"kind\": \"Option\",\n\
+             \x20     \"short\": \"o\",\n\
+             \x20     \"long\": \"out\",\n\
+             \x20     \"parameter_count\": {\n\
+             \x20       \"min\": 1,\n\
+             \x20       \"max\": null,\n\
+             \x20       \"rest\": false\n\
+             \x20     },\n\
+             \x20     \"value_name\": \"FILE\",\n\
+             \x20     \"value_hint\": \"FilePath\",\n\
+             \x20     \"delimiter\": \",\",\n\
+             \x20     \"key_value\": false,\n\
+             \x20     \"possible_values\": [\n\
+             \x20       \"a\",\n\
+             \x20       \"b\"\n\
+             \x20     ],\n\
+             \x20     \"env\": \"PROG_OUT\",\n\
+             \x20     \"default\": [\n\
+             \x20       \"a\"\n\
+             \x20     ],\n\
+             \x20     \"groups\": [\n\
+             \x20       \"targets\"\n\
+             \x20     ],\n\
+             \x20     \"help\": \"Write to the \\\"file\\\"\\nor C:\\\\dir\",\n\
+             \x20     \"long_help\": null,\n\
+             \x20     \"heading\": null,\n\
+             \x20     \"builtin\": null\n\
+             \x20   },\n"
+        ));
+        assert!(json.contains("      \"kind\": \"Subcommand\",\n      \"short\": null,\n      \"long\": null,\n"));
+        assert!(json.contains("        \"min\": 0,\n        \"max\": null,\n        \"rest\": true\n"));
+        assert!(json.contains("      \"env\": null,\n      \"default\": null,\n      \"groups\": [],\n"));
+        assert!(json.contains(
+            "      \"builtin\": \"Version\"\n\
+             \x20   }\n\
+             \x20 ],\n\
+             \x20 \"groups\": [\n\
+             \x20   {\n\
+             \x20     \"name\": \"targets\",\n\
+             \x20     \"args\": [\n\
+             \x20       \"out\",\n\
+             \x20       \"run\"\n\
+             \x20     ]\n\
+             \x20   }\n\
+             \x20 ]\n\
+             }\n"
+        ));
+    }
+}
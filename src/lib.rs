@@ -4,8 +4,18 @@
 
 mod config;
 mod arg;
+mod completion;
+mod help;
+mod json;
+mod man;
+mod markdown;
 mod parser;
 
+pub use completion::{ completion, completion_shim, Shell };
 pub use config::ConfigBuilder;
-pub use parser::{ parse, ParseError };
-pub use arg::{ Args, ParameterCount, args::* };
\ No newline at end of file
+pub use help::{ help, long_help, usage };
+pub use json::json;
+pub use man::{ man_page, man_pages };
+pub use markdown::{ check_markdown, markdown };
+pub use parser::{ expected, parse, parse_collecting, ErrorKind, Expected, Outcome, ParseError, ParseErrors };
+pub use arg::{ Args, ParameterCount, ValueHint, args::* };
\ No newline at end of file
@@ -0,0 +1,394 @@
+use crate::arg::{Arg, ArgType};
+use crate::config::Config;
+use crate::help::{fallbacks, placeholders, sections, usage};
+
+/// Heading of the custom help section which replaces the default EXIT STATUS
+const EXIT_STATUS: &str = "exit status";
+/// Heading of the custom help section which is added to ENVIRONMENT
+const ENVIRONMENT: &str = "environment";
+
+/// Generates a roff man page for a program from its Config, meant for section 1
+///
+/// The page has NAME and SYNOPSIS from the usage line, DESCRIPTION from the text before and
+/// after help, OPTIONS with the long description of every option and a subsection for every
+/// custom heading, COMMANDS, EXIT STATUS, ENVIRONMENT with the environment variable of every
+/// option that has one, a section for every custom help section, EXAMPLES and SEE ALSO
+/// with the pages of the subcommands
+/// # Note
+/// A custom help section with the heading "Exit status" replaces the default EXIT STATUS, and
+/// one with the heading "Environment" is added to ENVIRONMENT. Headings are matched ignoring case
+pub fn man_page(config: &Config, program_name: &str) -> String {
+    let mut page = header(config, program_name);
+
+    page.push_str(".SH NAME\n");
+    page.push_str(&escape(program_name));
+    if let Some(summary) = config.before_help.as_deref().and_then(|text| text.lines().next()) {
+        page.push_str(&format!(" \\- {}", escape(summary)));
+    }
+    page.push('\n');
+
+    let usage = usage(config, program_name);
+    page.push_str(&format!(
+        ".SH SYNOPSIS\n\\fB{}\\fR{}\n",
+        escape(program_name),
+        escape(&usage[program_name.len()..])
+    ));
+
+    let description: Vec<_> = config.before_help.iter().chain(&config.after_help).collect();
+    if !description.is_empty() {
+        page.push_str(".SH DESCRIPTION\n");
+        for (i, text) in description.into_iter().enumerate() {
+            if i > 0 {
+                page.push_str(".PP\n");
+            }
+            page.push_str(&paragraphs(text, ".PP"));
+        }
+    }
+
+    let (options, subcommands) = sections(config);
+    if !options.is_empty() {
+        page.push_str(".SH OPTIONS\n");
+        for (i, (heading, args)) in options.iter().enumerate() {
+            if i > 0 || heading != "Options" {
+                page.push_str(&format!(".SS {}\n", escape(heading)));
+            }
+            args.iter().for_each(|arg| page.push_str(&item(arg)));
+        }
+    }
+    if !subcommands.is_empty() {
+        page.push_str(".SH COMMANDS\n");
+        for (_, args) in &subcommands {
+            args.iter().for_each(|arg| page.push_str(&item(arg)));
+        }
+    }
+
+    page.push_str(".SH EXIT STATUS\n");
+    match custom_section(config, EXIT_STATUS) {
+        Some(body) => page.push_str(&paragraphs(body, ".PP")),
+        None => page.push_str("0 on success, and a non\\-zero status if an error occurred.\n"),
+    }
+
+    let variables: Vec<_> = config.args.iter().filter(|arg| arg.env.is_some()).collect();
+    let environment = custom_section(config, ENVIRONMENT);
+    if !variables.is_empty() || environment.is_some() {
+        page.push_str(".SH ENVIRONMENT\n");
+        for arg in variables {
+            page.push_str(&format!(".TP\n\\fB{}\\fR\n", escape(arg.env.as_deref().unwrap_or_default())));
+            page.push_str(&format!("Used for {} when it is not given.\n", names(arg).join(", ")));
+            if let Some(default) = &arg.default {
+                page.push_str(&format!("Defaults to {}.\n", escape(&default.join(", "))));
+            }
+        }
+        if let Some(body) = environment {
+            page.push_str(".PP\n");
+            page.push_str(&paragraphs(body, ".PP"));
+        }
+    }
+
+    for (heading, body) in &config.help_sections {
+        if [EXIT_STATUS, ENVIRONMENT].contains(&heading.to_lowercase().as_str()) {
+            continue;
+        }
+        page.push_str(&format!(".SH {}\n", escape(&heading.to_uppercase())));
+        page.push_str(&paragraphs(body, ".PP"));
+    }
+
+    if !config.examples.is_empty() {
+        page.push_str(".SH EXAMPLES\n");
+        for (command, description) in &config.examples {
+            page.push_str(&format!(".TP\n\\fB{}\\fR\n", escape(command)));
+            page.push_str(&paragraphs(description, ".IP"));
+        }
+    }
+
+    if !subcommands.is_empty() {
+        let pages: Vec<_> = subcommands
+            .iter()
+            .flat_map(|(_, args)| args)
+            .map(|arg| format!("\\fB{}\\fR(1)", escape(&page_name(program_name, arg))))
+            .collect();
+        page.push_str(&format!(".SH SEE ALSO\n{}\n", pages.join(", ")));
+    }
+
+    page
+}
+
+/// Generates the man page of a program followed by a page for every subcommand,
+/// each with the name of the page
+///
+/// Pages of subcommands are named after the program and the subcommand, as in
+/// ```prog-build```, and have NAME, SYNOPSIS, DESCRIPTION from the long description of the
+/// subcommand and SEE ALSO with the page of the program
+pub fn man_pages(config: &Config, program_name: &str) -> Vec<(String, String)> {
+    let mut pages = vec![(program_name.to_string(), man_page(config, program_name))];
+
+    for arg in config.args.iter().filter(|arg| arg.kind == ArgType::Subcommand) {
+        let name = page_name(program_name, arg);
+        let mut page = header(config, &name);
+
+        page.push_str(&format!(".SH NAME\n{}", escape(&name)));
+        if let Some(summary) = arg.help.as_deref().and_then(|help| help.lines().next()) {
+            page.push_str(&format!(" \\- {}", escape(summary)));
+        }
+        page.push('\n');
+
+        page.push_str(&format!(".SH SYNOPSIS\n\\fB{} {}\\fR", escape(program_name), escape(&arg.name)));
+        let placeholders = placeholders(arg);
+        if !placeholders.is_empty() {
+            page.push_str(&format!(" {}", escape(&placeholders)));
+        }
+        page.push('\n');
+
+        if let Some(help) = arg.long_help.as_ref().or(arg.help.as_ref()) {
+            page.push_str(".SH DESCRIPTION\n");
+            page.push_str(&paragraphs(help, ".PP"));
+        }
+
+        page.push_str(&format!(".SH SEE ALSO\n\\fB{}\\fR(1)\n", escape(program_name)));
+        pages.push((name, page));
+    }
+
+    pages
+}
+
+/// Title line of a page, with the version of the program as its source
+fn header(config: &Config, name: &str) -> String {
+    let source = match &config.version {
+        Some(version) => format!("{} {}", name, version),
+        None => name.to_string(),
+    };
+
+    format!(
+        ".TH {} 1 \"\" {} \"User Commands\"\n",
+        quoted(&name.to_uppercase()),
+        quoted(&source)
+    )
+}
+
+fn page_name(program_name: &str, subcommand: &Arg) -> String {
+    format!("{}-{}", program_name, subcommand.name)
+}
+
+/// Finds the body of the custom help section with the heading, ignoring case
+fn custom_section<'a>(config: &'a Config, heading: &str) -> Option<&'a str> {
+    config
+        .help_sections
+        .iter()
+        .find(|(h, _)| h.to_lowercase() == heading)
+        .map(|(_, body)| body.as_str())
+}
+
+/// Describes an argument as a tagged paragraph, with its names in bold and its parameters in italics
+fn item(arg: &Arg) -> String {
+    let mut item = format!(".TP\n{}", names(arg).join(", "));
+    let placeholders = placeholders(arg);
+    if !placeholders.is_empty() {
+        item.push_str(&format!(" \\fI{}\\fR", escape(&placeholders)));
+    }
+    item.push('\n');
+
+    if let Some(help) = arg.long_help.as_ref().or(arg.help.as_ref()) {
+        item.push_str(&paragraphs(help, ".IP"));
+    }
+    let fallbacks = fallbacks(arg);
+    if !fallbacks.is_empty() {
+        if arg.long_help.is_some() || arg.help.is_some() {
+            item.push_str(".IP\n");
+        }
+        item.push_str(&format!("{}\n", escape(&fallbacks)));
+    }
+
+    item
+}
+
+/// Names an argument is written with, in bold
+fn names(arg: &Arg) -> Vec<String> {
+    let mut names = vec![];
+    if arg.kind == ArgType::Subcommand {
+        names.push(arg.name.clone());
+    }
+    if let Some(short) = arg.short.filter(|_| arg.kind == ArgType::Option) {
+        names.push(format!("-{}", short));
+    }
+    if let Some(long) = arg.long.as_ref().filter(|_| arg.kind == ArgType::Option) {
+        names.push(format!("--{}", long));
+    }
+
+    names.iter().map(|name| format!("\\fB{}\\fR", escape(name))).collect()
+}
+
+/// Turns text into paragraphs, which are separated by empty lines in the text and by the request
+/// in the page
+fn paragraphs(text: &str, separator: &str) -> String {
+    let mut paragraphs = String::new();
+    let mut empty = false;
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            empty = !paragraphs.is_empty();
+            continue;
+        }
+        if empty {
+            paragraphs.push_str(separator);
+            paragraphs.push('\n');
+            empty = false;
+        }
+        paragraphs.push_str(&escape(line.trim_start()));
+        paragraphs.push('\n');
+    }
+
+    paragraphs
+}
+
+/// Escapes text so that roff shows it as is
+fn escape(text: &str) -> String {
+    let escaped = text.replace('\\', "\\e").replace('-', "\\-");
+
+    if escaped.starts_with('.') || escaped.starts_with('\'') {
+        format!("\\&{}", escaped)
+    } else {
+        escaped
+    }
+}
+
+/// Quotes an argument of a request
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", escape(text).replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{ConfigBuilder, ParameterCount};
+
+    #[test]
+    fn generated_man_page() {
+        let config = ConfigBuilder::default()
+            .add_option("out".into(), 'o', "out".into(), ParameterCount::Exact(1))
+            .add_long_flag("dry-run".into(), "dry-run".into())
+            .add_subcommand("build".into(), ParameterCount::More(0))
+            .set_help("out", "Write to the file".into())
+            .set_long_help("out", "Write to the file\n\n.txt is appended if missing".into())
+            .set_value_name("out", "FILE".into())
+            .set_env("out", "PROG_OUT".into())
+            .set_default("out", vec!["a.txt".into()])
+            .set_heading("dry-run", "Debugging".into())
+            .set_help("build", "Builds the targets".into())
+            .set_version("1.2.3".into())
+            .set_before_help("prog builds things\nfrom C:\\src".into())
+            .add_help_section("Exit status".into(), "0 on success".into())
+            .add_example("prog -o out build".into(), "Builds everything".into())
+            .build();
+
+        assert_eq!(
+            ".TH \"PROG\" 1 \"\" \"prog 1.2.3\" \"User Commands\"\n\
+             .SH NAME\n\
+             prog \\- prog builds things\n\
+             .SH SYNOPSIS\n\
+             \\fBprog\\fR [\\-o <FILE>] [\\-\\-dry\\-run] [build <BUILD>...] [<ARG>...]\n\
+             .SH DESCRIPTION\n\
+             prog builds things\n\
+             from C:\\esrc\n\
+             .SH OPTIONS\n\
+             .TP\n\
+             \\fB\\-o\\fR, \\fB\\-\\-out\\fR \\fI<FILE>\\fR\n\
+             Write to the file\n\
+             .IP\n\
+             \\&.txt is appended if missing\n\
+             .IP\n\
+             [env: PROG_OUT] [default: a.txt]\n\
+             .TP\n\
+             \\fB\\-h\\fR, \\fB\\-\\-help\\fR\n\
+             Prints help information\n\
+             .TP\n\
+             \\fB\\-V\\fR, \\fB\\-\\-version\\fR\n\
+             Prints version information\n\
+             .SS Debugging\n\
+             .TP\n\
+             \\fB\\-\\-dry\\-run\\fR\n\
+             .SH COMMANDS\n\
+             .TP\n\
+             \\fBbuild\\fR \\fI<BUILD>...\\fR\n\
+             Builds the targets\n\
+             .SH EXIT STATUS\n\
+             0 on success\n\
+             .SH ENVIRONMENT\n\
+             .TP\n\
+             \\fBPROG_OUT\\fR\n\
+             Used for \\fB\\-o\\fR, \\fB\\-\\-out\\fR when it is not given.\n\
+             Defaults to a.txt.\n\
+             .SH EXAMPLES\n\
+             .TP\n\
+             \\fBprog \\-o out build\\fR\n\
+             Builds everything\n\
+             .SH SEE ALSO\n\
+             \\fBprog\\-build\\fR(1)\n",
+            man_page(&config, "prog")
+        );
+    }
+
+    #[test]
+    fn exit_status_and_environment() {
+        let config = ConfigBuilder::default()
+            .add_long_flag("quiet".into(), "quiet".into())
+            .add_help_section("Environment".into(), "NO_COLOR disables colors".into())
+            .add_help_section("Files".into(), "~/.progrc".into())
+            .build();
+
+        let page = man_page(&config, "prog");
+
+        assert!(page.contains(
+            ".SH EXIT STATUS\n\
+             0 on success, and a non\\-zero status if an error occurred.\n\
+             .SH ENVIRONMENT\n\
+             .PP\n\
+             NO_COLOR disables colors\n\
+             .SH FILES\n\
+             ~/.progrc\n"
+        ));
+        assert!(!page.contains("SEE ALSO"));
+    }
+
+    #[test]
+    fn subcommand_pages() {
+        let config = ConfigBuilder::default()
+            .add_subcommand("build".into(), ParameterCount::More(0))
+            .add_subcommand("clean".into(), ParameterCount::Zero)
+            .set_help("build", "Builds the targets".into())
+            .set_long_help("build", "Builds the targets\n\nEvery target by default".into())
+            .set_value_name("build", "TARGET".into())
+            .set_version("1.2.3".into())
+            .build();
+
+        let pages = man_pages(&config, "prog");
+        let names: Vec<_> = pages.iter().map(|(name, _)| name.as_str()).collect();
+
+        assert_eq!(vec!["prog", "prog-build", "prog-clean"], names);
+        assert_eq!(man_page(&config, "prog"), pages[0].1);
+        assert_eq!(
+            ".TH \"PROG\\-BUILD\" 1 \"\" \"prog\\-build 1.2.3\" \"User Commands\"\n\
+             .SH NAME\n\
+             prog\\-build \\- Builds the targets\n\
+             .SH SYNOPSIS\n\
+             \\fBprog build\\fR <TARGET>...\n\
+             .SH DESCRIPTION\n\
+             Builds the targets\n\
+             .PP\n\
+             Every target by default\n\
+             .SH SEE ALSO\n\
+             \\fBprog\\fR(1)\n",
+            pages[1].1
+        );
+        assert_eq!(
+            ".TH \"PROG\\-CLEAN\" 1 \"\" \"prog\\-clean 1.2.3\" \"User Commands\"\n\
+             .SH NAME\n\
+             prog\\-clean\n\
+             .SH SYNOPSIS\n\
+             \\fBprog clean\\fR\n\
+             .SH SEE ALSO\n\
+             \\fBprog\\fR(1)\n",
+            pages[2].1
+        );
+    }
+}
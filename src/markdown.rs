@@ -0,0 +1,287 @@
+use crate::arg::{Arg, ParameterCount};
+use crate::config::Config;
+use crate::help::{placeholders, sections, usage};
+
+/// Generates a Markdown reference for a program from its Config
+///
+/// The reference has the usage line, a table of options for every help heading with their
+/// short and long names, parameters, arity, default, environment variable and description,
+/// a table of subcommands linking to a section with the long description of every subcommand,
+/// custom help sections and examples
+/// # Note
+/// Subcommands are anchored as ```#command-name```
+pub fn markdown(config: &Config, program_name: &str) -> String {
+    let mut reference = format!("# {}\n\n", program_name);
+
+    if let Some(before) = &config.before_help {
+        reference.push_str(&format!("{}\n\n", before));
+    }
+    reference.push_str(&format!("## Usage\n\n```\n{}\n```\n\n", usage(config, program_name)));
+
+    let (options, subcommands) = sections(config);
+    for (heading, args) in &options {
+        reference.push_str(&format!("## {}\n\n", heading));
+        reference.push_str("| Short | Long | Value | Arity | Default | Env | Description |\n");
+        reference.push_str("| --- | --- | --- | --- | --- | --- | --- |\n");
+        for arg in args {
+            reference.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {} |\n",
+                code(arg.short.map(|short| format!("-{}", short))),
+                code(arg.long.as_ref().map(|long| format!("--{}", long))),
+                code(Some(placeholders(arg)).filter(|placeholders| !placeholders.is_empty())),
+                arity(arg.parameter_count),
+                code(arg.default.as_ref().map(|default| default.join(", "))),
+                code(arg.env.clone()),
+                cell(&description(arg, false)),
+            ));
+        }
+        reference.push('\n');
+    }
+
+    for (heading, args) in &subcommands {
+        reference.push_str(&format!("## {}\n\n", heading));
+        reference.push_str("| Command | Arity | Description |\n");
+        reference.push_str("| --- | --- | --- |\n");
+        for arg in args {
+            reference.push_str(&format!(
+                "| [{}](#{}) | {} | {} |\n",
+                arg.name,
+                anchor(arg),
+                arity(arg.parameter_count),
+                cell(&description(arg, false)),
+            ));
+        }
+        reference.push('\n');
+
+        for arg in args {
+            reference.push_str(&format!("<a id=\"{}\"></a>\n\n### {}\n\n", anchor(arg), arg.name));
+            reference.push_str(&format!("```\n{} {}", program_name, arg.name));
+            let placeholders = placeholders(arg);
+            if !placeholders.is_empty() {
+                reference.push_str(&format!(" {}", placeholders));
+            }
+            reference.push_str("\n```\n\n");
+
+            let description = description(arg, true);
+            if !description.is_empty() {
+                reference.push_str(&format!("{}\n\n", description));
+            }
+        }
+    }
+
+    for (heading, body) in &config.help_sections {
+        reference.push_str(&format!("## {}\n\n{}\n\n", heading, body));
+    }
+
+    if !config.examples.is_empty() {
+        reference.push_str("## Examples\n\n");
+        for (command, description) in &config.examples {
+            reference.push_str(&format!("```\n{}\n```\n\n{}\n\n", command, description));
+        }
+    }
+
+    if let Some(after) = &config.after_help {
+        reference.push_str(&format!("{}\n\n", after));
+    }
+
+    reference.truncate(reference.trim_end().len());
+    reference.push('\n');
+    reference
+}
+
+/// Checks that a committed Markdown reference is the one generated from the Config
+///
+/// Meant for a test that keeps documentation from drifting away from the program, as in
+/// ```check_markdown(&config, "prog", include_str!("../docs/prog.md"))```
+/// # Returns
+/// ```Ok(())``` if the reference is up to date, else ```Err(message)``` with the first line
+/// that differs
+/// # Note
+/// Line endings are ignored, so a reference checked out with ```\r\n``` is still up to date
+pub fn check_markdown(config: &Config, program_name: &str, committed: &str) -> Result<(), String> {
+    let generated = markdown(config, program_name);
+    let mut generated_lines = generated.lines();
+    let mut committed_lines = committed.lines();
+
+    for line in 1.. {
+        match (generated_lines.next(), committed_lines.next()) {
+            (None, None) => break,
+            (generated, committed) if generated == committed => continue,
+            (generated, committed) => {
+                return Err(format!(
+                    "Markdown reference is out of date at line {}\n  expected: {}\n     found: {}",
+                    line,
+                    generated.unwrap_or("<end of file>"),
+                    committed.unwrap_or("<end of file>"),
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Description of an argument, the first line of it or the long one, followed by its possible values
+fn description(arg: &Arg, long: bool) -> String {
+    let help = match long {
+        true => arg.long_help.as_ref().or(arg.help.as_ref()),
+        false => arg.help.as_ref(),
+    };
+    let mut description = match help {
+        Some(help) if long => help.clone(),
+        Some(help) => help.lines().next().unwrap_or_default().to_string(),
+        None => String::new(),
+    };
+
+    if let Some(values) = &arg.possible_values {
+        let values: Vec<_> = values.iter().map(|value| format!("`{}`", value)).collect();
+        if !description.is_empty() {
+            description.push_str(if long { "\n\n" } else { " " });
+        }
+        description.push_str(&format!("One of {}.", values.join(", ")));
+    }
+
+    description
+}
+
+fn arity(parameter_count: ParameterCount) -> String {
+    match parameter_count {
+        ParameterCount::Zero => "none".to_string(),
+        parameter_count => parameter_count.to_string(),
+    }
+}
+
+fn anchor(arg: &Arg) -> String {
+    let slug: String = arg
+        .name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+
+    format!("command-{}", slug)
+}
+
+/// Formats a table cell as code, or as empty if there is nothing in it
+fn code(text: Option<String>) -> String {
+    match text {
+        Some(text) => format!("`{}`", cell(&text)),
+        None => String::new(),
+    }
+}
+
+/// Escapes text so that it stays in a single table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ConfigBuilder;
+
+    #[test]
+    fn generated_reference() {
+        let config = ConfigBuilder::default()
+            .add_option("out".into(), 'o', "out".into(), ParameterCount::Exact(1))
+            .add_long_option("color".into(), "color".into(), ParameterCount::Less(2))
+            .add_subcommand("build".into(), ParameterCount::More(0))
+            .add_subcommand("run_all".into(), ParameterCount::Rest)
+            .set_help("out", "Write to the file | pipe\nMore details".into())
+            .set_value_name("out", "FILE".into())
+            .set_env("out", "PROG_OUT".into())
+            .set_default("out", vec!["a.txt".into(), "b.txt".into()])
+            .set_possible_values("color", vec!["auto".into(), "never".into()])
+            .set_heading("color", "Display".into())
+            .set_help("build", "Builds the targets".into())
+            .set_long_help("build", "Builds the targets\n\nEvery target is built by default".into())
+            .set_before_help("prog builds things".into())
+            .add_help_section("Exit status".into(), "0 on success".into())
+            .add_example("prog build app".into(), "Builds the app".into())
+            .build();
+
+        assert_eq!(
+            "# prog\n\
+             \n\
+             prog builds things\n\
+             \n\
+             ## Usage\n\
+             \n\
+             ```\n\
+             prog [-o <FILE>] [--color [<COLOR>]] [build <BUILD>...] [run_all [<RUN_ALL>...]] [<ARG>...]\n\
+             ```\n\
+             \n\
+             ## Options\n\
+             \n\
+             | Short | Long | Value | Arity | Default | Env | Description |\n\
+             | --- | --- | --- | --- | --- | --- | --- |\n\
+             | `-o` | `--out` | `<FILE>` | exactly 1 | `a.txt, b.txt` | `PROG_OUT` | Write to the file \\| pipe |\n\
+             | `-h` | `--help` |  | none |  |  | Prints help information |\n\
+             \n\
+             ## Display\n\
+             \n\
+             | Short | Long | Value | Arity | Default | Env | Description |\n\
+             | --- | --- | --- | --- | --- | --- | --- |\n\
+             |  | `--color` | `[<COLOR>]` | less than 2 |  |  | One of `auto`, `never`. |\n\
+             \n\
+             ## Commands\n\
+             \n\
+             | Command | Arity | Description |\n\
+             | --- | --- | --- |\n\
+             | [build](#command-build) | at least 1 | Builds the targets |\n\
+             | [run_all](#command-run-all) | all remaining |  |\n\
+             \n\
+             <a id=\"command-build\"></a>\n\
+             \n\
+             ### build\n\
+             \n\
+             ```\n\
+             prog build <BUILD>...\n\
+             ```\n\
+             \n\
+             Builds the targets\n\
+             \n\
+             Every target is built by default\n\
+             \n\
+             <a id=\"command-run-all\"></a>\n\
+             \n\
+             ### run_all\n\
+             \n\
+             ```\n\
+             prog run_all [<RUN_ALL>...]\n\
+             ```\n\
+             \n\
+             ## Exit status\n\
+             \n\
+             0 on success\n\
+             \n\
+             ## Examples\n\
+             \n\
+             ```\n\
+             prog build app\n\
+             ```\n\
+             \n\
+             Builds the app\n",
+            markdown(&config, "prog")
+        );
+    }
+
+    #[test]
+    fn drift_check() {
+        let config = ConfigBuilder::default()
+            .add_short_flag("verbose".into(), 'v')
+            .build();
+        let reference = markdown(&config, "prog");
+
+        assert_eq!(Ok(()), check_markdown(&config, "prog", &reference));
+        assert_eq!(Ok(()), check_markdown(&config, "prog", &reference.replace('\n', "\r\n")));
+        assert_eq!(
+            Err("Markdown reference is out of date at line 1\n  expected: # prog\n     found: # old".to_string()),
+            check_markdown(&config, "prog", &reference.replace("# prog", "# old"))
+        );
+
+        let truncated = &reference[..reference.find("## Options").unwrap()];
+        let error = check_markdown(&config, "prog", truncated).unwrap_err();
+        assert!(error.ends_with("  expected: ## Options\n     found: <end of file>"));
+    }
+}
@@ -1,31 +1,116 @@
 mod error;
+mod partial;
+mod render;
+mod suggest;
+mod tokens;
 
 use std::env;
 use std::iter::{Iterator, Peekable};
 
-use crate::arg::{Arg, ArgType, Args, ParameterCount};
+use crate::arg::{Arg, ArgType, Args, Builtin, ParameterCount, EXTERNAL};
+use crate::completion::{candidates, COMPLETE_VAR};
 use crate::config::Config;
-pub use error::ParseError;
+use crate::help::{help, long_help, usage};
+use crate::json::json;
+pub use error::{ErrorKind, ParseError, ParseErrors};
+pub use partial::{expected, Expected};
+use tokens::Tokens;
+
+/// Represents the result of parsing that was not an Error
+#[derive(Debug)]
+pub enum Outcome {
+    /// CLI Arguments were parsed
+    Args(Args),
+    /// ```-h/--help``` was encountered, holds the generated short or long help,
+    /// or the JSON description of the program for ```--help=json```
+    Help(String),
+    /// ```-V/--version``` was encountered, holds the program name followed by the version
+    Version(String),
+    /// Completion was requested through the ```DCLP_COMPLETE``` environment variable,
+    /// holds candidates for the word under the cursor which are meant to be printed one per line
+    Completions(Vec<String>),
+}
 
 /// Parses CLI Arguments with the help of a Config
 /// # Returns
-/// If everything is ok returns ```Args``` else an Error
-pub fn parse(arg_config: Config) -> Result<Args, ParseError> {
+/// If everything is ok returns an ```Outcome``` else the first Error
+pub fn parse(arg_config: Config) -> Result<Outcome, ParseError> {
     let mut args = env::args();
-    let program_name = get_program_name(args.next().ok_or(ParseError::NoProgramName)?);
-    parse_inputs(arg_config, args.peekable(), program_name)
+    let program_name = get_program_name(args.next().ok_or_else(ParseError::no_program_name)?);
+    if let Some(position) = completion_position() {
+        return Ok(complete_inputs(&arg_config, args, position));
+    }
+    parse_outcome(arg_config, args.peekable(), program_name)
+}
+
+/// Parses CLI Arguments with the help of a Config without stopping on the first Error
+/// # Returns
+/// If everything is ok returns an ```Outcome``` else every Error that was encountered
+pub fn parse_collecting(arg_config: Config) -> Result<Outcome, ParseErrors> {
+    let mut args = env::args();
+    let program_name = match args.next() {
+        Some(path) => get_program_name(path),
+        None => return Err(ParseErrors::from(vec![ParseError::no_program_name()])),
+    };
+    if let Some(position) = completion_position() {
+        return Ok(complete_inputs(&arg_config, args, position));
+    }
+    collect_outcome(arg_config, args.peekable(), program_name)
 }
 
 fn get_program_name(path: String) -> String {
     let splitter = if cfg!(windows) { '\\' } else { '/' };
-    path.split(splitter).last().unwrap().to_string()
+    path.split(splitter).next_back().unwrap().to_string()
+}
+
+/// Gets the position of the word under the cursor if completion was requested
+fn completion_position() -> Option<usize> {
+    env::var(COMPLETE_VAR).ok()?.parse().ok()
+}
+
+fn complete_inputs(config: &Config, args: impl Iterator<Item = String>, position: usize) -> Outcome {
+    let words: Vec<_> = args.collect();
+    Outcome::Completions(candidates(config, &words, position))
+}
+
+fn parse_outcome(
+    config: Config,
+    args: Peekable<impl Iterator<Item = String>>,
+    program_name: String,
+) -> Result<Outcome, ParseError> {
+    match parse_tokens(&config, Tokens::new(args), program_name) {
+        (Outcome::Args(result), errors, _) => match errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(Outcome::Args(result)),
+        },
+        (outcome, _, _) => Ok(outcome),
+    }
 }
 
-fn parse_inputs(
+fn collect_outcome(
     config: Config,
-    mut args: Peekable<impl Iterator<Item = String>>,
+    args: Peekable<impl Iterator<Item = String>>,
+    program_name: String,
+) -> Result<Outcome, ParseErrors> {
+    match parse_tokens(&config, Tokens::new(args), program_name) {
+        (Outcome::Args(_), errors, _) if !errors.is_empty() => Err(ParseErrors::from(errors)),
+        (outcome, _, _) => Ok(outcome),
+    }
+}
+
+/// Parses every argument, recovering from errors by skipping unknown options and
+/// stopping the assignment of parameters to an argument on bad counts
+/// # Returns
+/// ```Outcome```, every Error in the order they were encountered and the argument which
+/// would take another parameter after the last one, with the number of parameters it has
+/// # Note
+/// Parsing stops as soon as a builtin argument like ```--help``` is encountered,
+/// Errors are meaningless in that case
+fn parse_tokens(
+    config: &Config,
+    mut args: Tokens<impl Iterator<Item = String>>,
     program_name: String,
-) -> Result<Args, ParseError> {
+) -> (Outcome, Vec<ParseError>, Option<(&Arg, usize)>) {
     let subcommands: Vec<_> = config
         .args
         .iter()
@@ -38,29 +123,75 @@ fn parse_inputs(
         .collect();
 
     let mut result = Args::new();
+    let mut errors = vec![];
 
     for arg in subcommands.iter().chain(options.iter()) {
         result.insert(arg.name.clone(), None);
     }
     result.insert(program_name.clone(), Some(vec![]));
+    if config.pass_through {
+        result.insert(EXTERNAL.to_string(), Some(vec![]));
+    }
+
+    let mut pending = None;
+    let mut appeared: Vec<(&Arg, usize)> = vec![];
 
     while let Some(arg) = args.next() {
+        pending = None;
+
         if arg.starts_with('-') {
-            let option = option_exists(&arg, &options).ok_or(ParseError::InvalidOption(arg))?;
+            let option = match option_exists(&arg, &options) {
+                Some(option) => option,
+                None if config.pass_through => {
+                    let external = result.get_mut(EXTERNAL).unwrap().as_mut().unwrap();
+                    external.push(arg);
+                    while let Some(param) = args.peek() {
+                        if param.starts_with('-') || subcommand_exists(param, &subcommands).is_some() {
+                            break;
+                        }
+                        external.push(args.next().unwrap());
+                    }
+                    continue;
+                }
+                None => {
+                    let suggestion = suggest::option(&arg, &options);
+                    errors.push(ParseError::invalid_option(arg, args.consumed() - 1, suggestion));
+                    continue;
+                }
+            };
+
+            match option.builtin {
+                Some(Builtin::Help) if arg.starts_with("--") && arg.ends_with("=json") => {
+                    return (Outcome::Help(json(config, &program_name)), errors, None);
+                }
+                Some(Builtin::Help) if arg.starts_with("--") => {
+                    return (Outcome::Help(long_help(config, &program_name)), errors, None);
+                }
+                Some(Builtin::Help) => return (Outcome::Help(help(config, &program_name)), errors, None),
+                Some(Builtin::Version) => {
+                    let version = config.version.clone().unwrap_or_default();
+                    return (Outcome::Version(format!("{} {}", program_name, version)), errors, None);
+                }
+                None => {}
+            }
 
-            assign_parameters(
+            appear(&mut appeared, option, args.consumed() - 1);
+            let count = assign_parameters(
                 &mut args,
-                option.parameter_count,
+                attached_parameter(&arg, option),
                 result.get_mut(&option.name).unwrap().get_or_insert(vec![]),
                 &subcommands,
                 &options,
                 option,
-            )?;
+                &mut errors,
+            );
+            pending = Some((option, count));
         } else {
-            if let Some(subcommand) = subcommands.iter().find(|a| a.name == arg) {
-                assign_parameters(
+            if let Some(subcommand) = subcommands.iter().copied().find(|a| a.name == arg) {
+                appear(&mut appeared, subcommand, args.consumed() - 1);
+                let count = assign_parameters(
                     &mut args,
-                    subcommand.parameter_count,
+                    None,
                     result
                         .get_mut(&subcommand.name)
                         .unwrap()
@@ -68,15 +199,67 @@ fn parse_inputs(
                     &subcommands,
                     &options,
                     subcommand,
-                )?;
+                    &mut errors,
+                );
+                pending = Some((subcommand, count));
+            } else if let Some(suggestion) = suggest::subcommand(&arg, &subcommands)
+                .filter(|_| !config.near_miss_parameters)
+            {
+                errors.push(ParseError::invalid_command(arg, args.consumed() - 1, Some(suggestion)));
             } else {
                 let val = result.get_mut(&program_name).unwrap().as_mut().unwrap();
-                val.push(arg);
+                if config.parameter_count.map_or(true, |count| count.takes_more(val.len())) {
+                    val.push(arg);
+                } else {
+                    let suggestion = suggest::subcommand(&arg, &subcommands);
+                    errors.push(ParseError::invalid_command(arg, args.consumed() - 1, suggestion));
+                }
+            }
+        }
+    }
+
+    for (_, names) in &config.groups {
+        let mut members = appeared.iter().filter(|(arg, _)| names.contains(&arg.name));
+        if let Some((first, _)) = members.next() {
+            for (arg, position) in members {
+                errors.push(ParseError::conflict(arg, first, *position));
             }
         }
     }
 
-    Ok(result)
+    for option in &options {
+        fall_back(option, result.get_mut(&option.name).unwrap(), &mut errors);
+    }
+
+    if let Some(parameter_count) = config.parameter_count {
+        let count = result[&program_name].as_ref().map_or(0, Vec::len);
+        if !parameter_count.is_satisfied_by(count) {
+            errors.push(ParseError::invalid_number_of_parameters(
+                program_name.clone(),
+                parameter_count,
+                Some(args.consumed()),
+                count,
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        let usage = usage(config, &program_name);
+        for error in &mut errors {
+            error.set_usage(usage.clone());
+        }
+    }
+
+    let pending = pending.filter(|(arg, count)| arg.parameter_count.takes_more(*count));
+
+    (Outcome::Args(result), errors, pending)
+}
+
+/// Records the position an argument first appeared at
+fn appear<'a>(appeared: &mut Vec<(&'a Arg, usize)>, arg: &'a Arg, position: usize) {
+    if appeared.iter().all(|(other, _)| other.name != arg.name) {
+        appeared.push((arg, position));
+    }
 }
 
 fn is_option_or_subcommand<'a>(
@@ -91,21 +274,24 @@ fn is_option_or_subcommand<'a>(
     }
 }
 
+/// Finds the option a token names, where ```--help=json``` names the help builtin
 fn option_exists<'a>(opt: &str, options: &Vec<&'a Arg>) -> Option<&'a Arg> {
-    if opt.starts_with("--") {
-        let long = &opt[2..];
+    if let Some(long) = opt.strip_prefix("--") {
         options
             .iter()
             .filter(|opt| opt.long.is_some())
-            .find(|opt| opt.long.as_ref().unwrap() == long)
-            .map(|opt| *opt)
+            .find(|opt| {
+                let name = opt.long.as_ref().unwrap();
+                name == long || (opt.builtin == Some(Builtin::Help) && long.strip_suffix("=json") == Some(name))
+            })
+            .copied()
     } else {
-        let c = opt.chars().skip(1).next()?;
+        let c = opt.chars().nth(1)?;
         options
             .iter()
             .filter(|opt| opt.short.is_some())
             .find(|opt| opt.short.unwrap() == c)
-            .map(|opt| *opt)
+            .copied()
     }
 }
 
@@ -113,90 +299,217 @@ fn subcommand_exists<'a>(sub: &str, subcommands: &Vec<&'a Arg>) -> Option<&'a Ar
     subcommands
         .iter()
         .find(|com| com.name == sub)
-        .map(|sub| *sub)
+        .copied()
+}
+
+/// Gets the parameter attached to a short option, as in ```-Dkey=value```
+/// # Note
+/// Only key=value options which take parameters can have one attached, for any other
+/// option the rest of the token is ignored like before
+fn attached_parameter(token: &str, option: &Arg) -> Option<String> {
+    if token.starts_with("--") || !option.key_value {
+        return None;
+    }
+    if let ParameterCount::Zero = option.parameter_count {
+        return None;
+    }
+
+    let attached: String = token.chars().skip(2).collect();
+    if attached.is_empty() {
+        None
+    } else {
+        Some(attached)
+    }
 }
 
+/// Assigns parameters to the argument that was just consumed
+/// # Returns
+/// Number of parameters the argument got, counting every value a parameter was split into
+/// # Note
+/// Errors are recorded at the position of the argument, except for rejected values
+/// which are recorded at the position of the parameter they came from
 fn assign_parameters(
-    args: &mut Peekable<impl Iterator<Item = String>>,
-    parameter_count: ParameterCount,
+    args: &mut Tokens<impl Iterator<Item = String>>,
+    attached: Option<String>,
     params: &mut Vec<String>,
     subcommands: &Vec<&Arg>,
     options: &Vec<&Arg>,
     arg: &Arg,
-) -> Result<(), ParseError> {
-    match parameter_count {
-        ParameterCount::Zero => {}
-        ParameterCount::Exact(n) => {
-            for i in 1..=n {
-                let param = args
-                    .next()
-                    .ok_or(ParseError::InvalidNumberOfParameters(format!(
-                        "There are only {} parameters to supply {} with instead of {}",
-                        i, arg, n,
-                    )))?;
-                if (param.starts_with('-') && option_exists(&param, options).is_some())
-                    || subcommand_exists(&param, subcommands).is_some()
-                {
-                    return Err(ParseError::InvalidNumberOfParameters(format!(
-                        "There are only {} parameters to supply {} with instead of {}",
-                        i, arg, n,
-                    )));
-                }
+    errors: &mut Vec<ParseError>,
+) -> usize {
+    let position = args.consumed() - 1;
+    let mut count = 0;
+
+    if let Some(param) = attached {
+        count += push_parameter(params, param, Some(position), arg, errors);
+    }
+
+    match arg.parameter_count {
+        ParameterCount::Rest => {
+            if count == 0 && args.peek().map(String::as_str) == Some("--") {
+                args.next();
+            }
+            for param in args {
                 params.push(param);
+                count += 1;
             }
         }
-        ParameterCount::More(n) => {
-            for param_count in 0.. {
-                if let Some(param) = args.peek() {
-                    let is_opt_or_sub =
-                        is_option_or_subcommand(param, options, subcommands).is_some();
-
-                    if is_opt_or_sub && param_count > n {
-                        break;
-                    } else if is_opt_or_sub && param_count <= n {
-                        return Err(ParseError::InvalidNumberOfParameters(format!(
-                            "{} expected at least {} parameters but got {}",
-                            arg,
-                            n + 1,
-                            param_count
-                        )));
+        parameter_count => {
+            while parameter_count.takes_more(count) {
+                match args.peek() {
+                    Some(param) if is_option_or_subcommand(param, options, subcommands).is_none() => {
+                        let param_position = args.consumed();
+                        let param = args.next().unwrap();
+                        count += push_parameter(params, param, Some(param_position), arg, errors);
                     }
-                    let param = args.next().unwrap();
-                    params.push(param);
-                } else {
-                    if param_count <= n {
-                        return Err(ParseError::InvalidNumberOfParameters(format!(
-                            "{} expected at least {} parameters but got {}",
-                            arg,
-                            n + 1,
-                            param_count
-                        )));
-                    }
-                    break;
+                    _ => break,
                 }
             }
         }
-        ParameterCount::Less(n) => {
-            for _ in 0..n-1 {
-                if let Some(param) = args.peek() {
-                    if is_option_or_subcommand(param, options, subcommands).is_some() {
-                        break;
-                    }
-                    let param = args.next().unwrap();
-                    params.push(param)
-                }
+    }
+
+    if !arg.parameter_count.is_satisfied_by(count) {
+        errors.push(ParseError::invalid_number_of_parameters(
+            arg.name.clone(),
+            arg.parameter_count,
+            Some(position),
+            count,
+        ));
+    }
+
+    count
+}
+
+/// Sets the parameters of an option which did not appear from its environment variable,
+/// or else to its default
+fn fall_back(option: &Arg, params: &mut Option<Vec<String>>, errors: &mut Vec<ParseError>) {
+    if params.is_some() {
+        return;
+    }
+
+    match option.env.as_ref().and_then(|var| env::var(var).ok()) {
+        Some(value) if option.parameter_count == ParameterCount::Zero => {
+            if !matches!(value.as_str(), "" | "0" | "false") {
+                *params = Some(vec![]);
             }
         }
+        Some(value) => {
+            let mut values = vec![];
+            let count = push_parameter(&mut values, value, None, option, errors);
+            if !option.parameter_count.is_satisfied_by(count) {
+                errors.push(ParseError::invalid_number_of_parameters(
+                    option.name.clone(),
+                    option.parameter_count,
+                    None,
+                    count,
+                ));
+            }
+            *params = Some(values);
+        }
+        None => *params = option.default.clone(),
     }
+}
+
+/// Pushes a parameter, split on the delimiter of the argument if it has one,
+/// and runs the key=value check, the possible values check and the validator of the argument
+/// on every resulting value
+/// # Note
+/// Rejected values are recorded as errors and are not pushed
+/// # Returns
+/// Number of values the parameter was split into, including rejected ones
+fn push_parameter(
+    params: &mut Vec<String>,
+    param: String,
+    position: Option<usize>,
+    arg: &Arg,
+    errors: &mut Vec<ParseError>,
+) -> usize {
+    let values = match arg.delimiter {
+        Some(delimiter) => split_parameter(&param, delimiter),
+        None => vec![param],
+    };
+
+    let count = values.len();
 
-    Ok(())
+    for value in values {
+        if arg.key_value && !is_key_value(&value) {
+            errors.push(ParseError::malformed_key_value(arg, value, position));
+            continue;
+        }
+        if let Some(possible_values) = &arg.possible_values {
+            if !possible_values.contains(&value) {
+                errors.push(ParseError::unknown_value(arg, value, position));
+                continue;
+            }
+        }
+        if let Some(validator) = &arg.validator {
+            if let Err(message) = validator.validate(&value) {
+                errors.push(ParseError::invalid_value(arg, value, position, message));
+                continue;
+            }
+        }
+        params.push(value);
+    }
+
+    count
+}
+
+fn is_key_value(value: &str) -> bool {
+    match value.find('=') {
+        Some(index) => index > 0,
+        None => false,
+    }
+}
+
+/// Splits a parameter on the delimiter, a delimiter preceded by '\\' is kept as is
+fn split_parameter(param: &str, delimiter: char) -> Vec<String> {
+    let mut values = vec![];
+    let mut value = String::new();
+    let mut chars = param.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' && chars.peek() == Some(&delimiter) {
+            value.push(chars.next().unwrap());
+        } else if c == delimiter {
+            values.push(std::mem::take(&mut value));
+        } else {
+            value.push(c);
+        }
+    }
+    values.push(value);
+
+    values
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::ConfigBuilder;
+    use crate::{external, key_values, ConfigBuilder};
+
+    /// Parses like ```parse_outcome``` but expects the outcome to be ```Args```
+    fn parse_inputs(
+        config: Config,
+        args: Peekable<impl Iterator<Item = String>>,
+        program_name: String,
+    ) -> Result<Args, ParseError> {
+        match parse_outcome(config, args, program_name)? {
+            Outcome::Args(args) => Ok(args),
+            outcome => panic!("Expected arguments, got {:?}", outcome),
+        }
+    }
+
+    /// Parses like ```collect_outcome``` but expects the outcome to be ```Args```
+    fn collect_inputs(
+        config: Config,
+        args: Peekable<impl Iterator<Item = String>>,
+        program_name: String,
+    ) -> Result<Args, ParseErrors> {
+        match collect_outcome(config, args, program_name)? {
+            Outcome::Args(args) => Ok(args),
+            outcome => panic!("Expected arguments, got {:?}", outcome),
+        }
+    }
 
     #[test]
     fn short_option_param_count_zero() {
@@ -241,7 +554,7 @@ mod tests {
             "this is the last one",
         ]
         .into_iter()
-        .map(|s| String::from(s));
+        .map(String::from);
 
         let result = parse_inputs(
             config,
@@ -287,7 +600,7 @@ mod tests {
             "now we're done",
         ]
         .into_iter()
-        .map(|s| String::from(s));
+        .map(String::from);
 
         let result = parse_inputs(
             config,
@@ -324,7 +637,7 @@ mod tests {
 
         let args = vec!["hello", "test1", "hello again", "test2"]
             .into_iter()
-            .map(|s| String::from(s));
+            .map(String::from);
 
         let result = parse_inputs(
             config,
@@ -353,7 +666,7 @@ mod tests {
 
         let args = vec!["hello", "test1", "hello again", "not yet test2", "test2"]
             .into_iter()
-            .map(|s| String::from(s));
+            .map(String::from);
 
         let result = parse_inputs(
             config,
@@ -391,7 +704,7 @@ mod tests {
             "--test2",
         ]
         .into_iter()
-        .map(|s| String::from(s));
+        .map(String::from);
 
         let result = parse_inputs(config, args.into_iter().peekable(), "long_option".into())
             .unwrap_or_else(|e| {
@@ -430,7 +743,7 @@ mod tests {
             "arg for test2",
         ]
         .into_iter()
-        .map(|s| String::from(s));
+        .map(String::from);
 
         let result = parse_inputs(
             config,
@@ -471,7 +784,7 @@ mod tests {
             "0arg", "-t", "1arg", "2arg", "--test1", "3arg", "4arg", "sub", "5arg", "6arg", "7arg",
         ]
         .into_iter()
-        .map(|s| String::from(s));
+        .map(String::from);
 
         let result = parse_inputs(
             config,
@@ -497,4 +810,545 @@ mod tests {
             result.get("sub").unwrap()
         )
     }
+
+    #[test]
+    fn validator() {
+        let config = ConfigBuilder::default()
+            .add_long_option("port".into(), "port".into(), ParameterCount::Exact(1))
+            .set_validator("port", |value| match value.parse::<u16>() {
+                Ok(port) if port > 0 => Ok(()),
+                _ => Err("must be a port 1-65535".into()),
+            })
+            .build();
+
+        let args = vec!["--port", "8080"].into_iter().map(String::from);
+
+        let result = parse_inputs(config, args.peekable(), "validator".into())
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(&Some(vec!["8080".into()]), result.get("port").unwrap());
+
+        let config = ConfigBuilder::default()
+            .add_long_option("port".into(), "port".into(), ParameterCount::Exact(1))
+            .set_validator("port", |value| match value.parse::<u16>() {
+                Ok(port) if port > 0 => Ok(()),
+                _ => Err("must be a port 1-65535".into()),
+            })
+            .build();
+
+        let args = vec!["--port", "70000"].into_iter().map(String::from);
+
+        let error = parse_inputs(config, args.peekable(), "validator".into()).unwrap_err();
+
+        assert_eq!(ErrorKind::InvalidValue, error.kind());
+        assert_eq!(Some("port"), error.arg());
+        assert_eq!(Some("70000"), error.token());
+        assert_eq!(Some(1), error.position());
+        assert_eq!(Some("must be a port 1-65535"), error.message());
+    }
+
+    #[test]
+    fn delimiter() {
+        let config = ConfigBuilder::default()
+            .add_long_option("tags".into(), "tags".into(), ParameterCount::More(0))
+            .add_short_option("pair".into(), 'p', ParameterCount::Exact(2))
+            .set_delimiter("tags", ',')
+            .set_delimiter("pair", ',')
+            .build();
+
+        let args = vec!["--tags", "a,b\\,c", "d", "-p", "x,y"]
+            .into_iter()
+            .map(String::from);
+
+        let result = parse_inputs(config, args.peekable(), "delimiter".into())
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(
+            &Some(vec!["a".into(), "b,c".into(), "d".into()]),
+            result.get("tags").unwrap()
+        );
+        assert_eq!(
+            &Some(vec!["x".into(), "y".into()]),
+            result.get("pair").unwrap()
+        );
+
+        let config = ConfigBuilder::default()
+            .add_short_option("pair".into(), 'p', ParameterCount::Exact(2))
+            .set_delimiter("pair", ',')
+            .build();
+
+        let args = vec!["-p", "x,y,z"].into_iter().map(String::from);
+
+        let error = parse_inputs(config, args.peekable(), "delimiter".into()).unwrap_err();
+
+        assert_eq!(ErrorKind::InvalidNumberOfParameters, error.kind());
+        assert_eq!(Some("pair"), error.arg());
+        assert_eq!(Some(ParameterCount::Exact(2)), error.expected());
+        assert_eq!(Some(3), error.found());
+    }
+
+    #[test]
+    fn key_value() {
+        let config = ConfigBuilder::default()
+            .add_short_option("define".into(), 'D', ParameterCount::Exact(1))
+            .add_long_option("set".into(), "set".into(), ParameterCount::Exact(1))
+            .set_key_value("define")
+            .set_key_value("set")
+            .build();
+
+        let args = vec!["-Dfoo=bar", "-D", "empty=", "--set", "a.b=c=d", "-Dfoo=baz"]
+            .into_iter()
+            .map(String::from);
+
+        let result = parse_inputs(config, args.peekable(), "key_value".into())
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        let define = key_values("define", &result).unwrap();
+        assert_eq!(2, define.len());
+        assert_eq!(Some(&"baz"), define.get("foo"));
+        assert_eq!(Some(&""), define.get("empty"));
+        assert_eq!(None, define.get("missing"));
+
+        let set = key_values("set", &result).unwrap();
+        assert_eq!(Some(&"c=d"), set.get("a.b"));
+
+        let config = ConfigBuilder::default()
+            .add_short_option("define".into(), 'D', ParameterCount::Exact(1))
+            .set_key_value("define")
+            .build();
+
+        let args = vec!["-D=bar"].into_iter().map(String::from);
+
+        let error = parse_inputs(config, args.peekable(), "key_value".into()).unwrap_err();
+
+        assert_eq!(ErrorKind::MalformedKeyValue, error.kind());
+        assert_eq!(Some("define"), error.arg());
+        assert_eq!(Some("=bar"), error.token());
+    }
+
+    #[test]
+    fn key_value_last_wins() {
+        let config = ConfigBuilder::default()
+            .add_short_option("define".into(), 'D', ParameterCount::More(0))
+            .set_key_value("define")
+            .build();
+
+        let args = vec!["-D", "mode=debug", "level=1", "mode=release"]
+            .into_iter()
+            .map(String::from);
+
+        let result = parse_inputs(config, args.peekable(), "key_value_last_wins".into())
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(Some(&"release"), key_values("define", &result).unwrap().get("mode"));
+        assert_eq!(
+            &Some(vec!["mode=debug".into(), "level=1".into(), "mode=release".into()]),
+            result.get("define").unwrap()
+        );
+    }
+
+    #[test]
+    fn attached_only_to_key_value() {
+        let config = ConfigBuilder::default()
+            .add_short_option("out".into(), 'o', ParameterCount::Exact(1))
+            .build();
+
+        let args = vec!["-ofile", "out.txt"].into_iter().map(String::from);
+
+        let result = parse_inputs(config, args.peekable(), "attached".into())
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(&Some(vec!["out.txt".into()]), result.get("out").unwrap());
+    }
+
+    #[test]
+    fn parameter_count_rest() {
+        let config = ConfigBuilder::default()
+            .add_short_flag("verbose".into(), 'v')
+            .add_subcommand("run".into(), ParameterCount::Rest)
+            .build();
+
+        let args = vec!["-v", "run", "--", "cargo", "run", "-v", "--release"]
+            .into_iter()
+            .map(String::from);
+
+        let result = parse_inputs(config, args.peekable(), "parameter_count_rest".into())
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(&Some(vec![]), result.get("verbose").unwrap());
+        assert_eq!(
+            &Some(vec![
+                "cargo".into(),
+                "run".into(),
+                "-v".into(),
+                "--release".into()
+            ]),
+            result.get("run").unwrap()
+        );
+    }
+
+    #[test]
+    fn pass_through() {
+        let config = ConfigBuilder::default()
+            .add_short_flag("verbose".into(), 'v')
+            .add_subcommand("sub".into(), ParameterCount::Zero)
+            .set_pass_through(true)
+            .build();
+
+        let args = vec!["--jobs", "4", "-v", "-x", "--color=always", "a", "b", "sub", "c"]
+            .into_iter()
+            .map(String::from);
+
+        let result = parse_inputs(config, args.peekable(), "pass_through".into())
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(
+            Some(&vec![
+                "--jobs".into(),
+                "4".into(),
+                "-x".into(),
+                "--color=always".into(),
+                "a".into(),
+                "b".into()
+            ]),
+            external(&result)
+        );
+        assert_eq!(&Some(vec![]), result.get("verbose").unwrap());
+        assert_eq!(&Some(vec![]), result.get("sub").unwrap());
+        assert_eq!(
+            &Some(vec!["c".into()]),
+            result.get("pass_through").unwrap()
+        );
+    }
+
+    #[test]
+    fn collect_errors() {
+        let config = ConfigBuilder::default()
+            .add_long_option("port".into(), "port".into(), ParameterCount::Exact(1))
+            .add_short_option("pair".into(), 'p', ParameterCount::Exact(2))
+            .add_subcommand("sub".into(), ParameterCount::Zero)
+            .set_validator("port", |value| {
+                value.parse::<u16>().map(|_| ()).map_err(|e| e.to_string())
+            })
+            .build();
+
+        let args = vec!["--verbose", "--port", "http", "-p", "a", "sub", "-x"]
+            .into_iter()
+            .map(String::from);
+
+        let errors = collect_inputs(config, args.peekable(), "collect_errors".into())
+            .unwrap_err();
+        let positions: Vec<_> = errors.iter().filter_map(ParseError::position).collect();
+        let kinds: Vec<_> = errors.iter().map(ParseError::kind).collect();
+
+        assert_eq!(vec![0, 2, 3, 6], positions);
+        assert_eq!(
+            vec![
+                ErrorKind::InvalidOption,
+                ErrorKind::InvalidValue,
+                ErrorKind::InvalidNumberOfParameters,
+                ErrorKind::InvalidOption
+            ],
+            kinds
+        );
+    }
+
+    #[test]
+    fn suggestions() {
+        let config = ConfigBuilder::default()
+            .add_option("version".into(), 'V', "version".into(), ParameterCount::Zero)
+            .add_short_flag("verbose".into(), 'v')
+            .add_subcommand("build".into(), ParameterCount::Zero)
+            .build();
+
+        let args = vec!["--verison", "-x", "buidl", "src"]
+            .into_iter()
+            .map(String::from);
+
+        let errors: Vec<_> = collect_inputs(config, args.peekable(), "suggestions".into())
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.to_string().lines().next().unwrap().to_string())
+            .collect();
+
+        assert_eq!(
+            vec![
+                "Invalid option encountered: --verison, did you mean `--version`?",
+                "Invalid option encountered: -x",
+                "Invalid subcommand encountered: buidl, did you mean `build`?",
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn near_miss_parameters() {
+        let config = ConfigBuilder::default()
+            .add_subcommand("run".into(), ParameterCount::Zero)
+            .add_subcommand("ls".into(), ParameterCount::Zero)
+            .build();
+
+        let args = vec!["fun", "file.txt"].into_iter().map(String::from);
+
+        let error = parse_inputs(config, args.peekable(), "prog".into()).unwrap_err();
+
+        assert_eq!(ErrorKind::InvalidCommand, error.kind());
+        assert_eq!(Some("fun"), error.token());
+        assert_eq!(Some("run"), error.suggestion());
+
+        let config = ConfigBuilder::default()
+            .add_subcommand("run".into(), ParameterCount::Zero)
+            .add_subcommand("ls".into(), ParameterCount::Zero)
+            .set_near_miss_parameters(true)
+            .build();
+
+        let args = vec!["fun", "l", "lsx", "file.txt"].into_iter().map(String::from);
+
+        let result = parse_inputs(config, args.peekable(), "prog".into())
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(
+            &Some(vec!["fun".into(), "l".into(), "lsx".into(), "file.txt".into()]),
+            result.get("prog").unwrap()
+        );
+    }
+
+    #[test]
+    fn builtin_names_taken() {
+        let config = ConfigBuilder::default()
+            .add_subcommand("help".into(), ParameterCount::Less(2))
+            .add_long_flag("version".into(), "show-version".into())
+            .set_version("1.0.0".into())
+            .build();
+
+        assert!(config.args.iter().all(|arg| arg.builtin.is_none()));
+
+        let args = vec!["--show-version", "help", "build"].into_iter().map(String::from);
+
+        let result = parse_inputs(config, args.peekable(), "prog".into())
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(&Some(vec![]), result.get("version").unwrap());
+        assert_eq!(&Some(vec!["build".into()]), result.get("help").unwrap());
+    }
+
+    #[test]
+    fn env_and_default() {
+        env::set_var("DCLP_TEST_OUT", "env.txt");
+        env::set_var("DCLP_TEST_TAGS", "a,b");
+        env::set_var("DCLP_TEST_QUIET", "false");
+        env::set_var("DCLP_TEST_COLOR", "sometimes");
+        env::remove_var("DCLP_TEST_UNSET");
+
+        let config = ConfigBuilder::default()
+            .add_long_option("out".into(), "out".into(), ParameterCount::Exact(1))
+            .add_long_option("tags".into(), "tags".into(), ParameterCount::Exact(2))
+            .add_long_option("jobs".into(), "jobs".into(), ParameterCount::Exact(1))
+            .add_long_option("level".into(), "level".into(), ParameterCount::Exact(1))
+            .add_long_flag("verbose".into(), "verbose".into())
+            .add_long_flag("quiet".into(), "quiet".into())
+            .set_env("out", "DCLP_TEST_OUT".into())
+            .set_default("out", vec!["default.txt".into()])
+            .set_env("tags", "DCLP_TEST_TAGS".into())
+            .set_delimiter("tags", ',')
+            .set_env("jobs", "DCLP_TEST_UNSET".into())
+            .set_default("jobs", vec!["4".into()])
+            .set_env("level", "DCLP_TEST_OUT".into())
+            .set_default("level", vec!["1".into()])
+            .set_env("verbose", "DCLP_TEST_OUT".into())
+            .set_env("quiet", "DCLP_TEST_QUIET".into())
+            .build();
+
+        let args = vec!["--level", "3"].into_iter().map(String::from);
+
+        let result = parse_inputs(config, args.peekable(), "env_and_default".into())
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(&Some(vec!["env.txt".into()]), result.get("out").unwrap());
+        assert_eq!(&Some(vec!["a".into(), "b".into()]), result.get("tags").unwrap());
+        assert_eq!(&Some(vec!["4".into()]), result.get("jobs").unwrap());
+        assert_eq!(&Some(vec!["3".into()]), result.get("level").unwrap());
+        assert_eq!(&Some(vec![]), result.get("verbose").unwrap());
+        assert_eq!(&None, result.get("quiet").unwrap());
+
+        let config = ConfigBuilder::default()
+            .add_long_option("color".into(), "color".into(), ParameterCount::Exact(1))
+            .set_possible_values("color", vec!["auto".into(), "never".into()])
+            .set_env("color", "DCLP_TEST_COLOR".into())
+            .build();
+
+        let error = parse_inputs(config, vec![].into_iter().peekable(), "env_and_default".into()).unwrap_err();
+
+        assert_eq!(ErrorKind::UnknownValue, error.kind());
+        assert_eq!(Some("sometimes"), error.token());
+        assert_eq!(None, error.position());
+    }
+
+    #[test]
+    fn program_parameter_count() {
+        let config = ConfigBuilder::default()
+            .add_subcommand("run".into(), ParameterCount::Zero)
+            .set_parameter_count(ParameterCount::Less(2))
+            .build();
+
+        let args = vec!["file.txt", "fun", "other"].into_iter().map(String::from);
+
+        let errors: Vec<_> = collect_inputs(config, args.peekable(), "prog".into())
+            .unwrap_err()
+            .into_iter()
+            .collect();
+
+        assert_eq!(2, errors.len());
+        assert_eq!(ErrorKind::InvalidCommand, errors[0].kind());
+        assert_eq!(Some("fun"), errors[0].token());
+        assert_eq!(Some("run"), errors[0].suggestion());
+        assert_eq!(Some("other"), errors[1].token());
+        assert_eq!(None, errors[1].suggestion());
+
+        let config = ConfigBuilder::default()
+            .set_parameter_count(ParameterCount::Exact(2))
+            .build();
+
+        let args = vec!["file.txt"].into_iter().map(String::from);
+
+        let error = parse_inputs(config, args.peekable(), "prog".into()).unwrap_err();
+
+        assert_eq!(ErrorKind::InvalidNumberOfParameters, error.kind());
+        assert_eq!(Some("prog"), error.arg());
+        assert_eq!(Some(1), error.found());
+    }
+
+    #[test]
+    fn help_short_circuits() {
+        let config = ConfigBuilder::default()
+            .add_long_option("port".into(), "port".into(), ParameterCount::Exact(1))
+            .add_short_flag("host".into(), 'h')
+            .build();
+
+        let args = vec!["--unknown", "--port", "--help", "-h"]
+            .into_iter()
+            .map(String::from);
+
+        match parse_outcome(config, args.peekable(), "prog".into()) {
+            Ok(Outcome::Help(help)) => {
+                assert!(help.contains("      --help"));
+                assert!(!help.contains("-h, --help"));
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn help_json_short_circuits() {
+        let config = ConfigBuilder::default()
+            .add_long_option("port".into(), "port".into(), ParameterCount::Exact(1))
+            .build();
+
+        let args = vec!["--port", "--help=json"].into_iter().map(String::from);
+
+        match parse_outcome(config, args.peekable(), "prog".into()) {
+            Ok(Outcome::Help(json)) => assert!(json.starts_with("{\n  \"name\": \"prog\",\n")),
+            other => panic!("{:?}", other),
+        }
+
+        let config = ConfigBuilder::default()
+            .add_long_flag("own-help".into(), "help".into())
+            .build();
+        let args = vec!["--help=json"].into_iter().map(String::from);
+
+        assert!(parse_outcome(config, args.peekable(), "prog".into()).is_err());
+    }
+
+    #[test]
+    fn version_short_circuits() {
+        let config = ConfigBuilder::default()
+            .add_long_option("port".into(), "port".into(), ParameterCount::Exact(1))
+            .set_validator("port", |_| Err("always rejected".into()))
+            .set_version("1.2.3".into())
+            .build();
+
+        let args = vec!["--port", "80", "-V"].into_iter().map(String::from);
+
+        match parse_outcome(config, args.peekable(), "prog".into()) {
+            Ok(Outcome::Version(version)) => assert_eq!("prog 1.2.3", version),
+            other => panic!("{:?}", other),
+        }
+
+        let config = ConfigBuilder::default().build();
+        let args = vec!["--version"].into_iter().map(String::from);
+
+        assert!(parse_outcome(config, args.peekable(), "prog".into()).is_err());
+    }
+
+    #[test]
+    fn possible_values() {
+        let config = ConfigBuilder::default()
+            .add_long_option("color".into(), "color".into(), ParameterCount::Exact(1))
+            .set_possible_values("color", vec!["auto".into(), "always".into(), "never".into()])
+            .build();
+
+        let args = vec!["--color", "sometimes"].into_iter().map(String::from);
+
+        let error = parse_inputs(config, args.peekable(), "possible_values".into()).unwrap_err();
+
+        assert_eq!(ErrorKind::UnknownValue, error.kind());
+        assert_eq!(Some("sometimes"), error.token());
+        assert_eq!(
+            Some(&["auto".to_string(), "always".into(), "never".into()][..]),
+            error.possible_values()
+        );
+        assert_eq!(
+            Some("Unknown value 'sometimes' for argument color"),
+            error.to_string().lines().next()
+        );
+        assert!(error
+            .render(&["prog".into(), "--color".into(), "sometimes".into()])
+            .contains("  help: possible values are auto, always, never\n"));
+    }
+
+    #[test]
+    fn groups() {
+        env::set_var("DCLP_TEST_FORMAT", "json");
+
+        let config = ConfigBuilder::default()
+            .add_long_flag("json".into(), "json".into())
+            .add_long_flag("yaml".into(), "yaml".into())
+            .add_long_option("format".into(), "format".into(), ParameterCount::Exact(1))
+            .add_subcommand("build".into(), ParameterCount::Zero)
+            .set_env("format", "DCLP_TEST_FORMAT".into())
+            .add_group("output".into(), vec!["json".into(), "yaml".into(), "format".into()])
+            .build();
+
+        let args = vec!["--yaml", "build", "--json", "--yaml"].into_iter().map(String::from);
+
+        let errors = collect_inputs(config, args.peekable(), "groups".into()).unwrap_err();
+        let errors: Vec<_> = errors.into_iter().collect();
+
+        assert_eq!(1, errors.len());
+        assert_eq!(ErrorKind::Conflict, errors[0].kind());
+        assert_eq!(Some("json"), errors[0].arg());
+        assert_eq!(Some("yaml"), errors[0].conflicts_with());
+        assert_eq!(Some(2), errors[0].position());
+        assert_eq!(
+            Some("Argument json can not be used with yaml"),
+            errors[0].to_string().lines().next()
+        );
+        assert!(errors[0]
+            .render(&["prog".into(), "--yaml".into(), "build".into(), "--json".into()])
+            .contains("  help: only one of yaml and json can be used\n"));
+
+        let config = ConfigBuilder::default()
+            .add_long_flag("json".into(), "json".into())
+            .add_long_option("format".into(), "format".into(), ParameterCount::Exact(1))
+            .set_env("format", "DCLP_TEST_FORMAT".into())
+            .add_group("output".into(), vec!["json".into(), "format".into()])
+            .build();
+
+        let args = vec!["--json"].into_iter().map(String::from);
+
+        let result = parse_inputs(config, args.peekable(), "groups".into()).unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(&Some(vec![]), result.get("json").unwrap());
+        assert_eq!(&Some(vec!["json".into()]), result.get("format").unwrap());
+    }
 }
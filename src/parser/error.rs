@@ -1,28 +1,331 @@
 use std::fmt;
 use std::error::Error;
 
-/// Represents an Error that happpens during parsing
-#[derive(Debug)]
-pub enum ParseError {
+use crate::arg::{Arg, ParameterCount};
+
+/// Represents the kind of an Error that happens during parsing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
     /// Constructed when unknown option is encountered
-    InvalidOption(String),
-    /// Constructed when unknown subcommand is encountered
-    InvalidCommand(String),
+    InvalidOption,
+    /// Constructed when unknown subcommand is encountered, which is a parameter one edit
+    /// away from the name of a subcommand or one the program does not take any more of
+    InvalidCommand,
     /// Constructed when the number of parameters is not satisfied
-    InvalidNumberOfParameters(String),
+    InvalidNumberOfParameters,
+    /// Constructed when a parameter is rejected by the validator of an argument
+    InvalidValue,
+    /// Constructed when a parameter is not one of the possible values of an argument
+    UnknownValue,
+    /// Constructed when a parameter of a key=value argument is not a ```key=value``` pair
+    MalformedKeyValue,
     /// Constructed when there are no CLI arguments
     NoProgramName,
+    /// Constructed when an argument appears after another argument of the same group did
+    Conflict,
+}
+
+/// Represents an Error that happpens during parsing
+/// # Note
+/// Which of the fields are set depends on the ```ErrorKind```
+#[derive(Debug)]
+pub struct ParseError {
+    kind: ErrorKind,
+    context: Box<Context>,
+}
+
+#[derive(Debug, Default)]
+struct Context {
+    arg: Option<String>,
+    token: Option<String>,
+    position: Option<usize>,
+    expected: Option<ParameterCount>,
+    found: Option<usize>,
+    message: Option<String>,
+    possible_values: Option<Vec<String>>,
+    suggestion: Option<String>,
+    conflicts_with: Option<String>,
+    usage: Option<String>,
+}
+
+impl ParseError {
+    fn new(kind: ErrorKind, context: Context) -> Self {
+        ParseError {
+            kind,
+            context: Box::new(context),
+        }
+    }
+
+    pub(crate) fn no_program_name() -> Self {
+        ParseError::new(ErrorKind::NoProgramName, Context::default())
+    }
+
+    pub(crate) fn invalid_option(option: String, position: usize, suggestion: Option<String>) -> Self {
+        ParseError::new(
+            ErrorKind::InvalidOption,
+            Context {
+                token: Some(option),
+                position: Some(position),
+                suggestion,
+                ..Context::default()
+            },
+        )
+    }
+
+    pub(crate) fn invalid_command(command: String, position: usize, suggestion: Option<String>) -> Self {
+        ParseError::new(
+            ErrorKind::InvalidCommand,
+            Context {
+                token: Some(command),
+                position: Some(position),
+                suggestion,
+                ..Context::default()
+            },
+        )
+    }
+
+    pub(crate) fn invalid_number_of_parameters(
+        arg: String,
+        expected: ParameterCount,
+        position: Option<usize>,
+        found: usize,
+    ) -> Self {
+        ParseError::new(
+            ErrorKind::InvalidNumberOfParameters,
+            Context {
+                arg: Some(arg),
+                position,
+                expected: Some(expected),
+                found: Some(found),
+                ..Context::default()
+            },
+        )
+    }
+
+    pub(crate) fn invalid_value(arg: &Arg, value: String, position: Option<usize>, message: String) -> Self {
+        ParseError::new(
+            ErrorKind::InvalidValue,
+            Context {
+                arg: Some(arg.name.clone()),
+                token: Some(value),
+                position,
+                message: Some(message),
+                ..Context::default()
+            },
+        )
+    }
+
+    pub(crate) fn unknown_value(arg: &Arg, value: String, position: Option<usize>) -> Self {
+        ParseError::new(
+            ErrorKind::UnknownValue,
+            Context {
+                arg: Some(arg.name.clone()),
+                token: Some(value),
+                position,
+                possible_values: arg.possible_values.clone(),
+                ..Context::default()
+            },
+        )
+    }
+
+    pub(crate) fn malformed_key_value(arg: &Arg, value: String, position: Option<usize>) -> Self {
+        ParseError::new(
+            ErrorKind::MalformedKeyValue,
+            Context {
+                arg: Some(arg.name.clone()),
+                token: Some(value),
+                position,
+                ..Context::default()
+            },
+        )
+    }
+
+    pub(crate) fn conflict(arg: &Arg, conflicts_with: &Arg, position: usize) -> Self {
+        ParseError::new(
+            ErrorKind::Conflict,
+            Context {
+                arg: Some(arg.name.clone()),
+                position: Some(position),
+                conflicts_with: Some(conflicts_with.name.clone()),
+                ..Context::default()
+            },
+        )
+    }
+
+    pub(crate) fn set_usage(&mut self, usage: String) {
+        self.context.usage = Some(usage);
+    }
+
+    /// Kind of the error
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Name of the argument the error is about
+    pub fn arg(&self) -> Option<&str> {
+        self.context.arg.as_deref()
+    }
+
+    /// Unknown option or subcommand, or the rejected value
+    pub fn token(&self) -> Option<&str> {
+        self.context.token.as_deref()
+    }
+
+    /// Position of the CLI argument the error happened at
+    /// # Note
+    /// Positions start at 0 with the first argument after the program name,
+    /// for a rejected value this is the position of the parameter it came from.
+    /// Missing parameters of the program are at the position after the last argument.
+    /// Errors in parameters read from an environment variable have no position
+    pub fn position(&self) -> Option<usize> {
+        self.context.position
+    }
+
+    /// Number of parameters the argument takes
+    pub fn expected(&self) -> Option<ParameterCount> {
+        self.context.expected
+    }
+
+    /// Number of parameters that were supplied to the argument
+    pub fn found(&self) -> Option<usize> {
+        self.context.found
+    }
+
+    /// Message returned by the validator which rejected the value
+    pub fn message(&self) -> Option<&str> {
+        self.context.message.as_deref()
+    }
+
+    /// Values the argument takes, which the unknown value is not one of
+    pub fn possible_values(&self) -> Option<&[String]> {
+        self.context.possible_values.as_deref()
+    }
+
+    /// Known option or subcommand with a spelling similar to the unknown one
+    pub fn suggestion(&self) -> Option<&str> {
+        self.context.suggestion.as_deref()
+    }
+
+    /// Argument of the same group which appeared before the conflicting one
+    pub fn conflicts_with(&self) -> Option<&str> {
+        self.context.conflicts_with.as_deref()
+    }
+
+    /// Usage line of the program the error happened in
+    pub fn usage(&self) -> Option<&str> {
+        self.context.usage.as_deref()
+    }
+
+    /// Describes the error without the suggestion
+    pub(crate) fn summary(&self) -> String {
+        let arg = self.arg().unwrap_or_default();
+        let token = self.token().unwrap_or_default();
+
+        match self.kind {
+            ErrorKind::InvalidOption => format!("Invalid option encountered: {}", token),
+            ErrorKind::InvalidCommand => format!("Invalid subcommand encountered: {}", token),
+            ErrorKind::InvalidNumberOfParameters => format!(
+                "The number of parameters constraint is not satisfied: {} expected {} parameters but got {}",
+                arg,
+                self.context.expected.unwrap_or_default(),
+                self.context.found.unwrap_or_default(),
+            ),
+            ErrorKind::InvalidValue => format!(
+                "Invalid value '{}' for argument {}: {}",
+                token,
+                arg,
+                self.message().unwrap_or_default(),
+            ),
+            ErrorKind::UnknownValue => format!("Unknown value '{}' for argument {}", token, arg),
+            ErrorKind::MalformedKeyValue => format!("Malformed key=value pair '{}' for argument {}", token, arg),
+            ErrorKind::NoProgramName => "There were no command line arguments.".to_string(),
+            ErrorKind::Conflict => format!(
+                "Argument {} can not be used with {}",
+                arg,
+                self.conflicts_with().unwrap_or_default(),
+            ),
+        }
+    }
+
+    fn fmt_without_usage(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.summary())?;
+
+        match self.suggestion() {
+            Some(suggestion) => write!(f, ", did you mean `{}`?", suggestion),
+            None => Ok(()),
+        }
+    }
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ParseError::InvalidOption(s) => write!(f, "Invalid option encountered: {}", s),
-            ParseError::InvalidCommand(s) => write!(f, "Invalid subcommand encountered: {}", s),
-            ParseError::InvalidNumberOfParameters(s) => write!(f, "The number of parameters constraint is not satisfied: {}", s),
-            ParseError::NoProgramName => write!(f, "There were no command line arguments."),
+        self.fmt_without_usage(f)?;
+
+        match self.usage() {
+            Some(usage) => write!(f, "\n\nUsage: {}", usage),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Represents every Error that happened during parsing
+#[derive(Debug)]
+pub struct ParseErrors {
+    errors: Vec<ParseError>,
+}
+
+impl ParseErrors {
+    /// Iterates over the errors in the order they were encountered
+    pub fn iter(&self) -> impl Iterator<Item = &ParseError> {
+        self.errors.iter()
+    }
+
+    /// Number of errors
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Checks if there are no errors
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl From<Vec<ParseError>> for ParseErrors {
+    fn from(errors: Vec<ParseError>) -> Self {
+        ParseErrors { errors }
+    }
+}
+
+impl IntoIterator for ParseErrors {
+    type Item = ParseError;
+    type IntoIter = std::vec::IntoIter<ParseError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            if let Some(position) = error.position() {
+                write!(f, "At argument {}: ", position)?;
+            }
+            error.fmt_without_usage(f)?;
+        }
+
+        match self.errors.iter().find_map(ParseError::usage) {
+            Some(usage) => write!(f, "\n\nUsage: {}", usage),
+            None => Ok(()),
         }
     }
 }
 
-impl Error for ParseError {}
\ No newline at end of file
+impl Error for ParseErrors {}
\ No newline at end of file
@@ -0,0 +1,152 @@
+use crate::arg::{ArgType, ParameterCount};
+use crate::config::Config;
+
+use super::tokens::Tokens;
+use super::{parse_tokens, Outcome};
+
+/// Name parameters of the program are kept under while a partial command line is parsed
+const PROGRAM: &str = "{program}";
+
+/// Represents something that can come at the cursor of a partial command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expected {
+    /// A parameter of the argument with the name
+    Value {
+        /// Name of the argument
+        arg: String,
+        /// Number of parameters the argument still needs
+        required: usize,
+        /// Number of parameters the argument still takes, ```None``` if there is no limit
+        allowed: Option<usize>,
+    },
+    /// Any option
+    Option,
+    /// One of the subcommands with the names
+    Subcommand(Vec<String>),
+    /// A parameter of the program
+    Positional,
+}
+
+/// Finds out what can come at the cursor of a partial command line
+/// # Returns
+/// Everything that is valid at the cursor, a parameter first. Nothing else is valid while
+/// a parameter is needed or when the argument takes every remaining argument
+/// # Note
+/// Tokens are the arguments after the program name and the cursor is a position among them,
+/// tokens at and after the cursor are ignored. The tokens before the cursor are parsed, so
+/// parameters are assigned to arguments exactly the way parsing assigns them
+pub fn expected(config: &Config, tokens: &[String], cursor: usize) -> Vec<Expected> {
+    let subcommands: Vec<_> = config
+        .args
+        .iter()
+        .filter(|arg| arg.kind == ArgType::Subcommand)
+        .map(|arg| arg.name.clone())
+        .collect();
+    let mut expected = vec![];
+
+    let tokens = tokens[..cursor.min(tokens.len())].iter().cloned().peekable();
+    let (outcome, _, pending) = parse_tokens(config, Tokens::new(tokens), PROGRAM.to_string());
+
+    if let Some((arg, count)) = pending {
+        let (required, allowed) = match arg.parameter_count {
+            ParameterCount::Zero => (0, Some(0)),
+            ParameterCount::Exact(n) => (n - count, Some(n - count)),
+            ParameterCount::More(n) => ((n + 1).saturating_sub(count), None),
+            ParameterCount::Less(n) => (0, Some(n - 1 - count)),
+            ParameterCount::Rest => (0, None),
+        };
+        expected.push(Expected::Value { arg: arg.name.clone(), required, allowed });
+
+        if required > 0 || arg.parameter_count == ParameterCount::Rest {
+            return expected;
+        }
+    }
+
+    expected.push(Expected::Option);
+    if !subcommands.is_empty() {
+        expected.push(Expected::Subcommand(subcommands));
+    }
+
+    let positionals = match &outcome {
+        Outcome::Args(args) => args[PROGRAM].as_ref().map_or(0, Vec::len),
+        _ => 0,
+    };
+    if pending.is_none() && config.parameter_count.map_or(true, |count| count.takes_more(positionals)) {
+        expected.push(Expected::Positional);
+    }
+
+    expected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ConfigBuilder;
+
+    #[test]
+    fn expected_after_partial_command_line() {
+        let config = ConfigBuilder::default()
+            .add_option("out".into(), 'o', "out".into(), ParameterCount::Exact(2))
+            .add_long_option("tags".into(), "tags".into(), ParameterCount::More(1))
+            .add_short_option("jobs".into(), 'j', ParameterCount::Less(3))
+            .add_subcommand("build".into(), ParameterCount::Zero)
+            .add_subcommand("run".into(), ParameterCount::Rest)
+            .set_delimiter("tags", ',')
+            .set_key_value("jobs")
+            .build();
+        let tokens: Vec<String> = vec!["-o", "a", "--tags", "x,y", "run", "--"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let value = |arg: &str, required, allowed| Expected::Value { arg: arg.into(), required, allowed };
+        let subcommands = Expected::Subcommand(vec!["build".into(), "run".into()]);
+
+        assert_eq!(
+            vec![Expected::Option, subcommands.clone(), Expected::Positional],
+            expected(&config, &tokens, 0)
+        );
+        assert_eq!(vec![value("out", 2, Some(2))], expected(&config, &tokens, 1));
+        assert_eq!(vec![value("out", 1, Some(1))], expected(&config, &tokens, 2));
+        assert_eq!(vec![value("tags", 2, None)], expected(&config, &tokens, 3));
+        assert_eq!(
+            vec![value("tags", 0, None), Expected::Option, subcommands.clone()],
+            expected(&config, &tokens, 4)
+        );
+        assert_eq!(vec![value("run", 0, None)], expected(&config, &tokens, 5));
+        assert_eq!(vec![value("run", 0, None)], expected(&config, &tokens, 6));
+        assert_eq!(
+            vec![value("jobs", 0, Some(1)), Expected::Option, subcommands.clone()],
+            expected(&config, &["-jn=1".into()], 1)
+        );
+        assert_eq!(
+            vec![Expected::Option, subcommands, Expected::Positional],
+            expected(&config, &["-jn=1".into(), "m=2".into()], 2)
+        );
+    }
+    #[test]
+    fn expected_agrees_with_parsing() {
+        let config = ConfigBuilder::default()
+            .add_short_flag("verbose".into(), 'v')
+            .add_subcommand("run".into(), ParameterCount::Zero)
+            .set_pass_through(true)
+            .set_parameter_count(ParameterCount::Exact(1))
+            .build();
+        let tokens: Vec<String> = vec!["-v", "--color", "always", "run", "input.txt", "rn"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let subcommands = Expected::Subcommand(vec!["run".into()]);
+
+        assert_eq!(
+            vec![Expected::Option, subcommands.clone(), Expected::Positional],
+            expected(&config, &tokens, 3)
+        );
+        assert_eq!(
+            vec![Expected::Option, subcommands.clone(), Expected::Positional],
+            expected(&config, &tokens, 4)
+        );
+        assert_eq!(vec![Expected::Option, subcommands.clone()], expected(&config, &tokens, 5));
+        assert_eq!(vec![Expected::Option, subcommands], expected(&config, &tokens, 6));
+    }
+}
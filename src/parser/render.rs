@@ -0,0 +1,174 @@
+use super::error::{ErrorKind, ParseError, ParseErrors};
+use super::get_program_name;
+use crate::help::display_width;
+
+impl ParseError {
+    /// Renders the error under the command line it happened in, with the offending
+    /// argument marked and a hint on how to fix it, or the end of the command line marked
+    /// when something is missing from it
+    ///
+    /// ```text
+    /// error: Invalid option encountered: --verison
+    ///   prog build --verison
+    ///              ^^^^^^^^^
+    ///   help: did you mean `--version`?
+    /// ```
+    /// # Note
+    /// The command line has to include the program name, as ```std::env::args``` does
+    pub fn render(&self, command_line: &[String]) -> String {
+        let mut rendered = self.render_without_usage(command_line);
+
+        if let Some(usage) = self.usage() {
+            rendered.push_str(&format!("\nUsage: {}\n", usage));
+        }
+
+        rendered
+    }
+
+    fn render_without_usage(&self, command_line: &[String]) -> String {
+        let mut rendered = format!("error: {}\n", self.summary());
+
+        if let (Some(position), Some((program, args))) = (self.position(), command_line.split_first()) {
+            let mut line = get_program_name(program.clone());
+            let mut marker = None;
+
+            for (i, arg) in args.iter().enumerate() {
+                let arg = quote(arg);
+                line.push(' ');
+                if i == position {
+                    marker = Some((display_width(&line), display_width(&arg)));
+                }
+                line.push_str(&arg);
+            }
+            if position == args.len() {
+                marker = Some((display_width(&line) + 1, 1));
+            }
+
+            rendered.push_str(&format!("  {}\n", line));
+            if let Some((offset, width)) = marker {
+                rendered.push_str(&format!("  {}{}\n", " ".repeat(offset), "^".repeat(width.max(1))));
+            }
+        }
+
+        if let Some(hint) = self.hint() {
+            rendered.push_str(&format!("  help: {}\n", hint));
+        }
+
+        rendered
+    }
+
+    fn hint(&self) -> Option<String> {
+        if let Some(suggestion) = self.suggestion() {
+            return Some(format!("did you mean `{}`?", suggestion));
+        }
+
+        let arg = self.arg().unwrap_or_default();
+        match self.kind() {
+            ErrorKind::InvalidNumberOfParameters => Some(format!(
+                "{} takes {} parameters",
+                arg,
+                self.expected().unwrap_or_default()
+            )),
+            ErrorKind::UnknownValue => Some(format!(
+                "possible values are {}",
+                self.possible_values().unwrap_or_default().join(", ")
+            )),
+            ErrorKind::MalformedKeyValue => Some(format!("parameters of {} are written as key=value", arg)),
+            ErrorKind::Conflict => Some(format!(
+                "only one of {} and {} can be used",
+                self.conflicts_with().unwrap_or_default(),
+                arg
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl ParseErrors {
+    /// Renders every error as ```ParseError::render``` does, separated by empty lines
+    /// and followed by the usage line once
+    pub fn render(&self, command_line: &[String]) -> String {
+        let mut rendered = self
+            .iter()
+            .map(|error| error.render_without_usage(command_line))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if let Some(usage) = self.iter().find_map(ParseError::usage) {
+            rendered.push_str(&format!("\nUsage: {}\n", usage));
+        }
+
+        rendered
+    }
+}
+
+/// Quotes an argument the way a shell would need it to be written
+fn quote(arg: &str) -> String {
+    if arg.is_empty() || arg.chars().any(|c| c.is_whitespace() || c == '\'' || c == '"') {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    } else {
+        arg.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::collect_outcome;
+    use crate::{ConfigBuilder, ParameterCount};
+
+    #[test]
+    fn caret_under_offending_argument() {
+        let config = ConfigBuilder::default()
+            .add_long_flag("version".into(), "version".into())
+            .add_long_option("message".into(), "message".into(), ParameterCount::Exact(1))
+            .add_subcommand("build".into(), ParameterCount::Zero)
+            .build();
+        let command_line: Vec<String> = vec!["/usr/bin/prog", "build", "--message", "hello world", "--verison"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let errors = collect_outcome(
+            config,
+            command_line[1..].iter().cloned().peekable(),
+            "prog".into(),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            "error: Invalid option encountered: --verison\n\
+             \x20 prog build --message 'hello world' --verison\n\
+             \x20                                    ^^^^^^^^^\n\
+             \x20 help: did you mean `--version`?\n\
+             \n\
+             Usage: prog [--version] [--message <MESSAGE>] [build] [<ARG>...]\n",
+            errors.render(&command_line)
+        );
+    }
+
+    #[test]
+    fn caret_after_last_argument() {
+        let config = ConfigBuilder::default()
+            .set_parameter_count(ParameterCount::Exact(2))
+            .set_parameter_name("FILE".into())
+            .build();
+        let command_line: Vec<String> = vec!["/usr/bin/prog", "a.txt"].into_iter().map(String::from).collect();
+
+        let errors = collect_outcome(
+            config,
+            command_line[1..].iter().cloned().peekable(),
+            "prog".into(),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            "error: The number of parameters constraint is not satisfied: prog expected exactly 2 parameters but got 1\n\
+             \x20 prog a.txt\n\
+             \x20            ^\n\
+             \x20 help: prog takes exactly 2 parameters\n\
+             \n\
+             Usage: prog <FILE> <FILE>\n",
+            errors.render(&command_line)
+        );
+    }
+}
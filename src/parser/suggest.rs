@@ -0,0 +1,74 @@
+use crate::arg::Arg;
+
+/// Finds the option closest to an unknown option, if it is close enough to be a likely typo
+/// # Note
+/// Both the token and the candidates are compared with their leading dashes, so ```-verbose```
+/// can suggest ```--verbose```
+pub fn option(token: &str, options: &[&Arg]) -> Option<String> {
+    let token = match token.strip_prefix("--") {
+        Some(long) => &token[..2 + long.find('=').unwrap_or(long.len())],
+        None => token,
+    };
+
+    options
+        .iter()
+        .flat_map(|opt| {
+            let long = opt.long.as_ref().map(|long| format!("--{}", long));
+            let short = opt.short.map(|short| format!("-{}", short));
+            long.into_iter().chain(short)
+        })
+        .map(|candidate| (distance(token, &candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= 2 && *distance * 3 <= candidate.chars().count())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Finds the subcommand which is exactly one edit away from a positional parameter
+pub fn subcommand(token: &str, subcommands: &[&Arg]) -> Option<String> {
+    subcommands
+        .iter()
+        .find(|sub| distance(token, &sub.name) == 1)
+        .map(|sub| sub.name.clone())
+}
+
+/// Computes the number of insertions, deletions, substitutions and transpositions of
+/// adjacent characters needed to turn one string into the other
+pub fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in table[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            table[i][j] = (table[i - 1][j] + 1)
+                .min(table[i][j - 1] + 1)
+                .min(table[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                table[i][j] = table[i][j].min(table[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    table[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_counts_transpositions_once() {
+        assert_eq!(0, distance("version", "version"));
+        assert_eq!(1, distance("verison", "version"));
+        assert_eq!(1, distance("buld", "build"));
+        assert_eq!(3, distance("", "abc"));
+    }
+}
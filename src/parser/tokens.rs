@@ -0,0 +1,32 @@
+use std::iter::{Iterator, Peekable};
+
+/// Iterator over CLI arguments which keeps track of their positions
+pub struct Tokens<I: Iterator<Item = String>> {
+    inner: Peekable<I>,
+    position: usize,
+}
+
+impl<I: Iterator<Item = String>> Tokens<I> {
+    pub fn new(inner: Peekable<I>) -> Self {
+        Tokens { inner, position: 0 }
+    }
+
+    /// Number of arguments consumed so far, which is also the position of the next one
+    pub fn consumed(&self) -> usize {
+        self.position
+    }
+
+    pub fn peek(&mut self) -> Option<&String> {
+        self.inner.peek()
+    }
+}
+
+impl<I: Iterator<Item = String>> Iterator for Tokens<I> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.inner.next()?;
+        self.position += 1;
+        Some(token)
+    }
+}